@@ -0,0 +1,58 @@
+use gtk::gio::{self, ApplicationCommandLine};
+use gtk::gio::prelude::*;
+use std::cell::{Cell, RefCell};
+
+// Kommandozeile eines Aufrufs, der mit --print auf die Auswahl eines Emojis wartet.
+// Solange sie hier gehalten wird, wartet auch der aufrufende Prozess (ggf. eine zweite Instanz).
+// Wird sie freigegeben, beendet sich der Aufrufer mit dem gesetzten Exit-Code.
+//
+// Kam --print beim Start der primären Instanz selbst, beendet sie sich nach der Auswahl.
+// GApplication meldet dann immer 0, daher reicht main() den Exit-Code aus LOKALER_EXIT_CODE weiter.
+thread_local! {
+    static WARTENDER_AUFRUF: RefCell<Option<ApplicationCommandLine>> = const { RefCell::new(None) };
+    static LOKALER_EXIT_CODE: Cell<Option<i32>> = const { Cell::new(None) };
+}
+
+pub fn warte_auf_auswahl(cmdline: &ApplicationCommandLine) {
+    WARTENDER_AUFRUF.with(|aufruf| {
+        // Ein älterer, noch wartender Aufruf wird ohne Auswahl beendet
+        if let Some(alt) = aufruf.replace(Some(cmdline.clone())) {
+            alt.set_exit_status(1);
+        }
+    });
+}
+
+// Gibt das gewählte Emoji auf stdout des Aufrufers aus. true, wenn jemand gewartet hat.
+pub fn melde_auswahl(emoji: &str) -> bool {
+    match WARTENDER_AUFRUF.with(|aufruf| aufruf.borrow_mut().take()) {
+        Some(cmdline) => {
+            cmdline.print_literal(&format!("{}\n", emoji));
+            beende(cmdline, 0);
+            true
+        }
+        None => false,
+    }
+}
+
+// Fenster ohne Auswahl geschlossen (Escape, Schließen-Knopf) → Aufrufer endet mit Exit-Code 1
+pub fn melde_abbruch() {
+    if let Some(cmdline) = WARTENDER_AUFRUF.with(|aufruf| aufruf.borrow_mut().take()) {
+        beende(cmdline, 1);
+    }
+}
+
+fn beende(cmdline: ApplicationCommandLine, code: i32) {
+    cmdline.set_exit_status(code);
+
+    if !cmdline.is_remote() {
+        LOKALER_EXIT_CODE.with(|exit_code| exit_code.set(Some(code)));
+        if let Some(app) = gio::Application::default() {
+            app.quit();
+        }
+    }
+}
+
+// Exit-Code eines lokalen --print-Aufrufs, falls die Instanz deshalb beendet wurde
+pub fn lokaler_exit_code() -> Option<i32> {
+    LOKALER_EXIT_CODE.with(|exit_code| exit_code.get())
+}
@@ -0,0 +1,591 @@
+use crate::i18n::Sprache;
+
+// ╔══════════════════════════════════════════════════════════════╗
+// ║           Kommandozeile: Befehle, Optionen und Hilfe         ║
+// ╚══════════════════════════════════════════════════════════════╝
+//
+//   emoji-picker [OPTIONEN]                  → Befehl::Fenster   (GTK, ggf. laufende Instanz)
+//   emoji-picker --dmenu [--select [--type]] → Befehl::Dmenu
+//   emoji-picker search <WÖRTER> …           → Befehl::Suche
+//   emoji-picker pick <EMOJI>                → Befehl::Waehlen
+//   emoji-picker shortcut [AKTION] …         → Befehl::Shortcut
+//   emoji-picker stats …                     → Befehl::Statistik
+//   emoji-picker i18n check|template …       → Befehl::Uebersetzung
+//   emoji-picker completions <SHELL>         → Befehl::Vervollstaendigung
+//
+//   --lang, --debug, --config-dir, --portable, --help und --version gelten überall.
+//   Die Tabellen ALLGEMEIN, FENSTER und UNTERBEFEHLE liefern die Texte für --help
+//   und die Vervollständigung (vervollstaendigung.rs) – neue Optionen dort eintragen.
+
+// Kommandozeilen-Optionen, die auch an eine bereits laufende Instanz weitergereicht werden
+#[derive(Debug, Default, Clone)]
+pub struct Startoptionen {
+    pub suche: Option<String>,      // --query <text>
+    pub kategorie: Option<String>,  // --category <name>, --tab <name|history>
+    pub sprache: Option<String>,    // --lang <code>
+    pub ausgabe: bool,              // --print
+    pub debug: bool,                // --debug
+}
+
+impl Startoptionen {
+    // Keine Aktion angefordert → ein erneuter Aufruf schaltet das Fenster nur um
+    pub fn ist_leer(&self) -> bool {
+        self.suche.is_none() && self.kategorie.is_none() && self.sprache.is_none() && !self.ausgabe
+    }
+}
+
+// Wohin das Fenster beim Öffnen springt (Kommandozeile oder D-Bus), angewendet vor present()
+#[derive(Debug, Default, Clone)]
+pub struct Startziel {
+    pub seite: Option<u32>,         // Notebook-Seite, 0 = 🕓-Verlauf
+    pub suche: Option<String>,      // vorausgefülltes Suchfeld
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutAktion {
+    Status,
+    Install,
+    Remove,
+    Snippet,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UebersetzungAktion {
+    Pruefen(Option<String>),    // check [CODE]
+    Vorlage(String),            // template CODE
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ausgabeformat {
+    Tabelle,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone)]
+pub enum Befehl {
+    Fenster(Startoptionen),
+    Hilfe,
+    Version,
+    Dmenu { auswaehlen: bool, tippen: bool },
+    Suche { begriffe: Vec<String>, limit: Option<usize>, json: bool },
+    Waehlen(String),
+    Shortcut { aktion: ShortcutAktion, trocken: bool },
+    Statistik { top: Option<usize>, nach_kategorie: bool, format: Ausgabeformat },
+    Uebersetzung(UebersetzungAktion),
+    Vervollstaendigung(Shell),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliFehler {
+    UnbekannteOption(String),
+    FehlenderWert(String),              // Option oder Unterbefehl ohne Argument
+    UngueltigerWert(String, String),    // Option, Wert
+    Unerwartet(String),
+}
+
+impl CliFehler {
+    pub fn meldung(&self, sprachpaket: &Sprache) -> String {
+        let text = match self {
+            CliFehler::UnbekannteOption(option)       => sprachpaket.cli_error_unknown_option.replace("{option}", option),
+            CliFehler::FehlenderWert(option)          => sprachpaket.cli_error_missing_value.replace("{option}", option),
+            CliFehler::UngueltigerWert(option, wert)  => sprachpaket.cli_error_invalid_value.replace("{option}", option).replace("{value}", wert),
+            CliFehler::Unerwartet(option)             => sprachpaket.cli_error_unexpected.replace("{option}", option),
+        };
+        format!("❌ {}\n{}", text, sprachpaket.cli_error_hint)
+    }
+}
+
+// Ergebnis der Auswertung. --lang, --debug, --config-dir und --portable stehen auch bei Fehlern
+// bereit, damit die Fehlermeldung schon in der gewünschten Sprache erscheint.
+#[derive(Debug, Clone)]
+pub struct Aufruf {
+    pub sprache: Option<String>,
+    pub debug: bool,
+    pub konfig_ordner: Option<String>,      // --config-dir <DIR>
+    pub portabel: bool,                     // --portable
+    pub befehl: Result<Befehl, CliFehler>,
+}
+
+impl Aufruf {
+    pub fn aus_argumenten(args: &[String]) -> Self {
+        // args[0] ist der Programmname
+        let mut sprache = None;
+        let mut debug = false;
+        let mut konfig_ordner = None;
+        let mut portabel = false;
+        let mut i = 1;
+        while i < args.len() {
+            match args[i].as_str() {
+                "--lang" if i + 1 < args.len()       => { sprache = Some(args[i + 1].clone()); i += 1; }
+                "--config-dir" if i + 1 < args.len() => { konfig_ordner = Some(args[i + 1].clone()); i += 1; }
+                "--debug"                            => debug = true,
+                "--portable"                         => portabel = true,
+                "--"                                 => break,
+                _                                    => {}
+            }
+            i += 1;
+        }
+
+        Aufruf {
+            sprache,
+            debug,
+            konfig_ordner,
+            portabel,
+            befehl: lies_befehl(args),
+        }
+    }
+}
+
+// ─── Tabellen für Hilfe und Vervollständigung ───────────────────
+
+// Eine Zeile der Hilfe: Namen, Platzhalter für den Wert und Beschreibung aus dem Sprachpaket
+pub struct Eintrag {
+    pub namen: &'static [&'static str],
+    pub wert: Option<&'static str>,
+    pub werte: fn() -> Vec<String>,         // feste Werte für die Vervollständigung
+    pub hilfe: fn(&Sprache) -> &String,
+}
+
+pub struct Unterbefehl {
+    pub name: &'static str,
+    pub argument: &'static str,             // Platzhalter in der Hilfe
+    pub werte: fn() -> Vec<String>,
+    pub optionen: &'static [Eintrag],
+    pub hilfe: fn(&Sprache) -> &String,
+}
+
+fn keine() -> Vec<String> {
+    Vec::new()
+}
+
+fn sprachen() -> Vec<String> {
+    Sprache::finde_verfuegbare_sprachen(false)
+        .into_iter()
+        .map(|sprache| sprache.code)
+        .filter(|code| code != "system")
+        .collect()
+}
+
+pub fn kategorien() -> Vec<String> {
+    std::iter::once("history")
+        .chain(crate::KATEGORIEN.iter().filter_map(|(datei, _)| datei.strip_suffix(".list")))
+        .map(str::to_string)
+        .collect()
+}
+
+fn shortcut_aktionen() -> Vec<String> {
+    ["status", "install", "remove", "snippet"].map(String::from).to_vec()
+}
+
+fn formate() -> Vec<String> {
+    ["table", "json", "csv"].map(String::from).to_vec()
+}
+
+fn uebersetzung_aktionen() -> Vec<String> {
+    ["check", "template"].map(String::from).to_vec()
+}
+
+fn shells() -> Vec<String> {
+    ["bash", "zsh", "fish"].map(String::from).to_vec()
+}
+
+pub const ALLGEMEIN: &[Eintrag] = &[
+    Eintrag { namen: &["-h", "--help"],    wert: None,         werte: keine,    hilfe: |s| &s.cli_help_help },
+    Eintrag { namen: &["-V", "--version"], wert: None,         werte: keine,    hilfe: |s| &s.cli_help_version },
+    Eintrag { namen: &["--lang"],          wert: Some("CODE"), werte: sprachen, hilfe: |s| &s.cli_help_lang },
+    Eintrag { namen: &["--debug"],         wert: None,         werte: keine,    hilfe: |s| &s.cli_help_debug },
+    Eintrag { namen: &["--config-dir"],    wert: Some("DIR"),  werte: keine,    hilfe: |s| &s.cli_help_config_dir },
+    Eintrag { namen: &["--portable"],      wert: None,         werte: keine,    hilfe: |s| &s.cli_help_portable },
+];
+
+pub const FENSTER: &[Eintrag] = &[
+    Eintrag { namen: &["--query"],             wert: Some("TEXT"), werte: keine,      hilfe: |s| &s.cli_help_query },
+    Eintrag { namen: &["--category", "--tab"], wert: Some("NAME"), werte: kategorien, hilfe: |s| &s.cli_help_category },
+    Eintrag { namen: &["--print"],             wert: None,         werte: keine,      hilfe: |s| &s.cli_help_print },
+    Eintrag { namen: &["--dmenu"],             wert: None,         werte: keine,      hilfe: |s| &s.cli_help_dmenu },
+    Eintrag { namen: &["--select"],            wert: None,         werte: keine,      hilfe: |s| &s.cli_help_select },
+    Eintrag { namen: &["--type"],              wert: None,         werte: keine,      hilfe: |s| &s.cli_help_type },
+    Eintrag { namen: &["-S", "--setup"],       wert: None,         werte: keine,      hilfe: |s| &s.cli_help_setup },
+];
+
+pub const UNTERBEFEHLE: &[Unterbefehl] = &[
+    Unterbefehl {
+        name: "search",
+        argument: "<WORDS>",
+        werte: keine,
+        optionen: &[
+            Eintrag { namen: &["--limit"], wert: Some("N"), werte: keine, hilfe: |s| &s.cli_help_limit },
+            Eintrag { namen: &["--json"],  wert: None,      werte: keine, hilfe: |s| &s.cli_help_json },
+        ],
+        hilfe: |s| &s.cli_help_search,
+    },
+    Unterbefehl {
+        name: "pick",
+        argument: "<EMOJI>",
+        werte: keine,
+        optionen: &[],
+        hilfe: |s| &s.cli_help_pick,
+    },
+    Unterbefehl {
+        name: "shortcut",
+        argument: "[status|install|remove|snippet]",
+        werte: shortcut_aktionen,
+        optionen: &[
+            Eintrag { namen: &["--dry-run"], wert: None, werte: keine, hilfe: |s| &s.cli_help_dry_run },
+        ],
+        hilfe: |s| &s.cli_help_shortcut,
+    },
+    Unterbefehl {
+        name: "stats",
+        argument: "",
+        werte: keine,
+        optionen: &[
+            Eintrag { namen: &["--top"],         wert: Some("N"),      werte: keine,   hilfe: |s| &s.cli_help_top },
+            Eintrag { namen: &["--by-category"], wert: None,           werte: keine,   hilfe: |s| &s.cli_help_by_category },
+            Eintrag { namen: &["--format"],      wert: Some("FORMAT"), werte: formate, hilfe: |s| &s.cli_help_format },
+        ],
+        hilfe: |s| &s.cli_help_stats,
+    },
+    Unterbefehl {
+        name: "i18n",
+        argument: "<check|template> [CODE]",
+        werte: uebersetzung_aktionen,
+        optionen: &[],
+        hilfe: |s| &s.cli_help_i18n,
+    },
+    Unterbefehl {
+        name: "completions",
+        argument: "<bash|zsh|fish>",
+        werte: shells,
+        optionen: &[],
+        hilfe: |s| &s.cli_help_completions,
+    },
+];
+
+// ─── Auswertung ─────────────────────────────────────────────────
+
+// Stelle des Unterbefehls: erstes Argument, das weder Option noch Wert einer Option ist
+// (dieselbe Regel wie in der bash-Vervollständigung), z.B. 3 bei "--lang de stats"
+fn finde_unterbefehl(args: &[String]) -> Option<usize> {
+    let mit_wert = |arg: &str| {
+        ALLGEMEIN.iter().chain(FENSTER).any(|eintrag| eintrag.wert.is_some() && eintrag.namen.contains(&arg))
+    };
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--" => return None,
+            arg if mit_wert(arg) => i += 1,
+            arg if arg.starts_with('-') && arg.len() > 1 => {}
+            arg => return UNTERBEFEHLE.iter().any(|befehl| befehl.name == arg).then_some(i),
+        }
+        i += 1;
+    }
+    None
+}
+
+fn lies_befehl(args: &[String]) -> Result<Befehl, CliFehler> {
+    let stelle = finde_unterbefehl(args);
+    let unterbefehl = stelle.map(|i| args[i].as_str());
+    let mut leser = args
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(i, _)| Some(*i) != stelle)
+        .map(|(_, arg)| arg.as_str());
+
+    let fenster = unterbefehl.is_none();
+    let mut optionen = Startoptionen::default();
+    let mut schalter: Vec<&str> = Vec::new();       // gesetzte Optionen ohne Wert
+    let mut positionen: Vec<String> = Vec::new();
+    let mut limit = None;
+    let mut format = Ausgabeformat::Tabelle;
+
+    while let Some(arg) = leser.next() {
+        match arg {
+            "-h" | "--help"    => return Ok(Befehl::Hilfe),
+            "-V" | "--version" => return Ok(Befehl::Version),
+            "--debug"          => optionen.debug = true,
+            "--lang"           => optionen.sprache = Some(wert(arg, &mut leser)?),
+            "--config-dir"     => { wert(arg, &mut leser)?; }     // schon in Aufruf ausgewertet
+            "--portable"       => {}
+
+            "--query" if fenster => optionen.suche = Some(wert(arg, &mut leser)?),
+            "--category" | "--tab" if fenster => {
+                let name = wert(arg, &mut leser)?;
+                if crate::kategorie_seite(&name).is_none() {
+                    return Err(CliFehler::UngueltigerWert(arg.to_string(), name));
+                }
+                optionen.kategorie = Some(name);
+            }
+            "--print" if fenster => optionen.ausgabe = true,
+            "--dmenu" | "--select" | "--type" | "-S" | "--setup" if fenster => schalter.push(arg),
+
+            "--limit" if unterbefehl == Some("search") => limit = Some(anzahl(arg, &mut leser)?),
+            "--top" if unterbefehl == Some("stats") => limit = Some(anzahl(arg, &mut leser)?),
+            "--format" if unterbefehl == Some("stats") => {
+                format = match wert(arg, &mut leser)?.as_str() {
+                    "table" => Ausgabeformat::Tabelle,
+                    "json"  => Ausgabeformat::Json,
+                    "csv"   => Ausgabeformat::Csv,
+                    andere  => return Err(CliFehler::UngueltigerWert(arg.to_string(), andere.into())),
+                }
+            }
+            "--by-category" if unterbefehl == Some("stats") => schalter.push(arg),
+            "--json" if unterbefehl == Some("search") => schalter.push(arg),
+            "--dry-run" if unterbefehl == Some("shortcut") => schalter.push(arg),
+
+            // Alles danach sind Argumente, auch wenn sie mit '-' beginnen
+            "--" => positionen.extend(leser.by_ref().map(str::to_string)),
+            option if option.starts_with('-') && option.len() > 1 => {
+                return Err(CliFehler::UnbekannteOption(option.to_string()));
+            }
+            _ => positionen.push(arg.to_string()),
+        }
+    }
+
+    let gesetzt = |name: &str| schalter.contains(&name);
+
+    match unterbefehl {
+        None => {
+            if let Some(arg) = positionen.first() {
+                return Err(CliFehler::Unerwartet(arg.clone()));
+            }
+            if gesetzt("-S") || gesetzt("--setup") {
+                return Ok(Befehl::Shortcut { aktion: ShortcutAktion::Install, trocken: false });
+            }
+            if gesetzt("--dmenu") {
+                // --dmenu ersetzt das Fenster, Fensteroptionen passen nicht dazu
+                if optionen.suche.is_some() || optionen.kategorie.is_some() || optionen.ausgabe {
+                    return Err(CliFehler::Unerwartet("--dmenu".into()));
+                }
+                if gesetzt("--type") && !gesetzt("--select") {
+                    return Err(CliFehler::Unerwartet("--type".into()));
+                }
+                return Ok(Befehl::Dmenu { auswaehlen: gesetzt("--select"), tippen: gesetzt("--type") });
+            }
+            if let Some(option) = ["--select", "--type"].into_iter().find(|option| gesetzt(option)) {
+                return Err(CliFehler::Unerwartet(option.into()));
+            }
+            Ok(Befehl::Fenster(optionen))
+        }
+        Some("search") => {
+            if positionen.is_empty() {
+                return Err(CliFehler::FehlenderWert("search".into()));
+            }
+            Ok(Befehl::Suche { begriffe: positionen, limit, json: gesetzt("--json") })
+        }
+        Some("pick") => match positionen.as_slice() {
+            [emoji] => Ok(Befehl::Waehlen(emoji.clone())),
+            [] => Err(CliFehler::FehlenderWert("pick".into())),
+            [_, weiterer, ..] => Err(CliFehler::Unerwartet(weiterer.clone())),
+        },
+        Some("shortcut") => {
+            let trocken = gesetzt("--dry-run");
+            let aktion = match positionen.as_slice() {
+                // Ohne Aktion: anzeigen – mit --dry-run zeigen, was install ändern würde
+                [] if trocken => ShortcutAktion::Install,
+                []            => ShortcutAktion::Status,
+                [aktion] => match aktion.as_str() {
+                    "status"  => ShortcutAktion::Status,
+                    "install" => ShortcutAktion::Install,
+                    "remove"  => ShortcutAktion::Remove,
+                    "snippet" => ShortcutAktion::Snippet,
+                    andere    => return Err(CliFehler::UngueltigerWert("shortcut".into(), andere.into())),
+                },
+                [_, weiterer, ..] => return Err(CliFehler::Unerwartet(weiterer.clone())),
+            };
+            Ok(Befehl::Shortcut { aktion, trocken })
+        }
+        Some("stats") => match positionen.first() {
+            Some(arg) => Err(CliFehler::Unerwartet(arg.clone())),
+            None => Ok(Befehl::Statistik { top: limit, nach_kategorie: gesetzt("--by-category"), format }),
+        },
+        Some("i18n") => match positionen.as_slice() {
+            [aktion, rest @ ..] if aktion == "check" => match rest {
+                [] => Ok(Befehl::Uebersetzung(UebersetzungAktion::Pruefen(None))),
+                [code] => Ok(Befehl::Uebersetzung(UebersetzungAktion::Pruefen(Some(sprachcode(code)?)))),
+                [_, weiterer, ..] => Err(CliFehler::Unerwartet(weiterer.clone())),
+            },
+            [aktion, rest @ ..] if aktion == "template" => match rest {
+                [code] => Ok(Befehl::Uebersetzung(UebersetzungAktion::Vorlage(sprachcode(code)?))),
+                [] => Err(CliFehler::FehlenderWert("template".into())),
+                [_, weiterer, ..] => Err(CliFehler::Unerwartet(weiterer.clone())),
+            },
+            [andere, ..] => Err(CliFehler::UngueltigerWert("i18n".into(), andere.clone())),
+            [] => Err(CliFehler::FehlenderWert("i18n".into())),
+        },
+        Some("completions") => match positionen.as_slice() {
+            [shell] => match shell.as_str() {
+                "bash" => Ok(Befehl::Vervollstaendigung(Shell::Bash)),
+                "zsh"  => Ok(Befehl::Vervollstaendigung(Shell::Zsh)),
+                "fish" => Ok(Befehl::Vervollstaendigung(Shell::Fish)),
+                andere => Err(CliFehler::UngueltigerWert("completions".into(), andere.into())),
+            },
+            [] => Err(CliFehler::FehlenderWert("completions".into())),
+            [_, weiterer, ..] => Err(CliFehler::Unerwartet(weiterer.clone())),
+        },
+        Some(_) => unreachable!("Unterbefehl steht in UNTERBEFEHLE"),
+    }
+}
+
+// Wert einer Option, z.B. "herz" bei "--query herz"
+fn wert<'a>(option: &str, leser: &mut impl Iterator<Item = &'a str>) -> Result<String, CliFehler> {
+    leser
+        .next()
+        .map(str::to_string)
+        .ok_or_else(|| CliFehler::FehlenderWert(option.to_string()))
+}
+
+// Positive Zahl als Wert, z.B. "5" bei "--limit 5"
+fn anzahl<'a>(option: &str, leser: &mut impl Iterator<Item = &'a str>) -> Result<usize, CliFehler> {
+    let text = wert(option, leser)?;
+    text.parse::<usize>()
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or_else(|| CliFehler::UngueltigerWert(option.to_string(), text))
+}
+
+// Sprachcode wie "de" oder "pt-BR" – landet im Dateinamen, daher nur Buchstaben, Ziffern, '-' und '_'
+fn sprachcode(code: &str) -> Result<String, CliFehler> {
+    let gueltig = !code.is_empty() && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if gueltig {
+        Ok(code.to_string())
+    } else {
+        Err(CliFehler::UngueltigerWert("i18n".into(), code.to_string()))
+    }
+}
+
+// ─── Hilfe und Version ──────────────────────────────────────────
+
+const SPALTE: usize = 30;
+
+fn hilfezeile(links: &str, text: &str) -> String {
+    let breite = links.chars().count();
+    if breite + 2 > SPALTE {
+        format!("{}\n{}{}\n", links, " ".repeat(SPALTE), text)
+    } else {
+        format!("{}{}{}\n", links, " ".repeat(SPALTE - breite), text)
+    }
+}
+
+// "  -h, --help" bzw. "      --lang <CODE>" – ohne Kurzform eingerückt, damit die langen Namen bündig stehen
+fn eintrag_links(eintrag: &Eintrag, einzug: &str) -> String {
+    let kurz = if eintrag.namen[0].starts_with("--") { "    " } else { "" };
+    let namen = eintrag.namen.join(", ");
+    match eintrag.wert {
+        Some(wert) => format!("{}{}{} <{}>", einzug, kurz, namen, wert),
+        None => format!("{}{}{}", einzug, kurz, namen),
+    }
+}
+
+pub fn hilfe(sprachpaket: &Sprache) -> String {
+    let aufruf = format!("{}: ", sprachpaket.cli_usage);
+    let einzug = " ".repeat(aufruf.chars().count());
+
+    let mut text = format!("\n{}emoji-picker [OPTIONS]\n", aufruf);
+    text += &format!("{}emoji-picker <COMMAND> [OPTIONS]\n", einzug);
+
+    text += &format!("\n{}:\n", sprachpaket.cli_options);
+    for eintrag in ALLGEMEIN.iter().chain(FENSTER) {
+        text += &hilfezeile(&eintrag_links(eintrag, "  "), (eintrag.hilfe)(sprachpaket));
+    }
+
+    text += &format!("\n{}:\n", sprachpaket.cli_commands);
+    for befehl in UNTERBEFEHLE {
+        let links = format!("  {} {}", befehl.name, befehl.argument);
+        text += &hilfezeile(links.trim_end(), (befehl.hilfe)(sprachpaket));
+        for eintrag in befehl.optionen {
+            text += &hilfezeile(&eintrag_links(eintrag, "    "), (eintrag.hilfe)(sprachpaket));
+        }
+    }
+    text
+}
+
+pub fn version(sprachpaket: &Sprache) -> String {
+    format!(
+        "Emoji Picker 📦 Version: {}\nCopyright © {}\n{}: {}\n{}",
+        env!("CARGO_PKG_VERSION"),
+        env!("CARGO_PKG_AUTHORS"),
+        sprachpaket.cli_license,
+        env!("CARGO_PKG_LICENSE"),
+        env!("CARGO_PKG_REPOSITORY"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aufruf(zeile: &str) -> Aufruf {
+        let args: Vec<String> = std::iter::once("emoji-picker").chain(zeile.split_whitespace()).map(String::from).collect();
+        Aufruf::aus_argumenten(&args)
+    }
+
+    fn befehl(zeile: &str) -> Result<Befehl, CliFehler> {
+        aufruf(zeile).befehl
+    }
+
+    #[test]
+    fn optionen_vor_und_nach_dem_unterbefehl() {
+        for zeile in ["--debug shortcut status", "shortcut --debug status", "shortcut status --debug"] {
+            assert!(matches!(befehl(zeile), Ok(Befehl::Shortcut { aktion: ShortcutAktion::Status, trocken: false })), "{zeile}");
+            assert!(aufruf(zeile).debug, "{zeile}");
+        }
+        for zeile in ["--lang de stats --top 3", "stats --lang de --top 3", "--config-dir /tmp/x --portable stats --top 3"] {
+            assert!(matches!(befehl(zeile), Ok(Befehl::Statistik { top: Some(3), nach_kategorie: false, format: Ausgabeformat::Tabelle })), "{zeile}");
+        }
+        assert_eq!(aufruf("--lang de stats").sprache.as_deref(), Some("de"));
+        assert_eq!(aufruf("--config-dir /tmp/x stats").konfig_ordner.as_deref(), Some("/tmp/x"));
+
+        // Der Wert einer Option ist kein Unterbefehl
+        assert!(matches!(befehl("--query search"), Ok(Befehl::Fenster(Startoptionen { suche: Some(ref text), .. })) if text == "search"));
+        assert!(matches!(befehl("--lang de search herz --limit 2"), Ok(Befehl::Suche { ref begriffe, limit: Some(2), json: false }) if begriffe == &["herz"]));
+        // Nach "--" ist alles Argument
+        assert_eq!(befehl("-- stats").unwrap_err(), CliFehler::Unerwartet("stats".into()));
+    }
+
+    #[test]
+    fn fehlender_wert() {
+        assert_eq!(befehl("--lang").unwrap_err(), CliFehler::FehlenderWert("--lang".into()));
+        assert_eq!(befehl("--query").unwrap_err(), CliFehler::FehlenderWert("--query".into()));
+        assert_eq!(befehl("stats --top").unwrap_err(), CliFehler::FehlenderWert("--top".into()));
+        assert_eq!(befehl("search").unwrap_err(), CliFehler::FehlenderWert("search".into()));
+        assert_eq!(befehl("stats --top 0").unwrap_err(), CliFehler::UngueltigerWert("--top".into(), "0".into()));
+    }
+
+    #[test]
+    fn unbekannte_option() {
+        assert_eq!(befehl("--gibtsnicht").unwrap_err(), CliFehler::UnbekannteOption("--gibtsnicht".into()));
+        // Optionen gelten nur dort, wo sie hingehören
+        assert_eq!(befehl("stats --json").unwrap_err(), CliFehler::UnbekannteOption("--json".into()));
+        assert_eq!(befehl("--query herz stats").unwrap_err(), CliFehler::UnbekannteOption("--query".into()));
+        assert_eq!(befehl("herz").unwrap_err(), CliFehler::Unerwartet("herz".into()));
+    }
+
+    #[test]
+    fn kategorie_und_tab() {
+        for zeile in ["--category flags", "--tab flags"] {
+            assert!(matches!(befehl(zeile), Ok(Befehl::Fenster(Startoptionen { kategorie: Some(ref name), .. })) if name == "flags"), "{zeile}");
+        }
+        assert!(matches!(befehl("--tab history"), Ok(Befehl::Fenster(Startoptionen { kategorie: Some(ref name), .. })) if name == "history"));
+        assert_eq!(befehl("--tab gibtsnicht").unwrap_err(), CliFehler::UngueltigerWert("--tab".into(), "gibtsnicht".into()));
+    }
+
+    #[test]
+    fn dmenu_auswaehlen_tippen() {
+        assert!(matches!(befehl("--dmenu"), Ok(Befehl::Dmenu { auswaehlen: false, tippen: false })));
+        assert!(matches!(befehl("--dmenu --select"), Ok(Befehl::Dmenu { auswaehlen: true, tippen: false })));
+        assert!(matches!(befehl("--dmenu --select --type"), Ok(Befehl::Dmenu { auswaehlen: true, tippen: true })));
+        assert_eq!(befehl("--dmenu --type").unwrap_err(), CliFehler::Unerwartet("--type".into()));
+        assert_eq!(befehl("--select").unwrap_err(), CliFehler::Unerwartet("--select".into()));
+        assert_eq!(befehl("--dmenu --query herz").unwrap_err(), CliFehler::Unerwartet("--dmenu".into()));
+    }
+}
@@ -0,0 +1,127 @@
+use gtk::gio;
+use gtk::prelude::*;
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{Duration, SystemTime},
+};
+
+// ╔══════════════════════════════════════════════════════════════╗
+// ║        Änderungen von außen im Konfigurationsordner          ║
+// ╚══════════════════════════════════════════════════════════════╝
+//
+//   lade_emojies / speichere_emojies / lade_settings / speichere_settings
+//         └─► merke(pfad)           Stand = Änderungszeit + Größe nach eigenem Lesen/Schreiben
+//
+//   ~/.config/emoji-picker/   (gio::FileMonitor)
+//         │  Editor speichert settings.ini oder smileys.list
+//         ▼
+//   Ereignisse 300 ms sammeln ──► extern_geaendert()? ── nein ──► eigenes Schreiben, ignorieren
+//         │ ja
+//         ▼
+//   reaktion(&[Aenderung::Einstellungen, Aenderung::Liste("smileys.list")])
+//
+//   Vor dem Speichern: extern_geaendert() ──► ja: erst neu laden bzw. zusammenführen
+
+#[derive(Debug, PartialEq)]
+pub enum Aenderung {
+    Einstellungen,
+    Liste(String),      // Dateiname, z.B. "smileys.list"
+}
+
+type Stand = Option<(SystemTime, u64)>;     // None: Datei fehlt
+
+thread_local! {
+    static BEKANNT: RefCell<HashMap<PathBuf, Stand>> = RefCell::new(HashMap::new());
+}
+
+fn stand(pfad: &Path) -> Stand {
+    fs::metadata(pfad)
+        .ok()
+        .map(|daten| (daten.modified().unwrap_or(SystemTime::UNIX_EPOCH), daten.len()))
+}
+
+// Nach jedem eigenen Lesen oder Schreiben aufrufen
+pub fn merke(pfad: &Path) {
+    BEKANNT.with(|bekannt| bekannt.borrow_mut().insert(pfad.to_path_buf(), stand(pfad)));
+}
+
+// Hat jemand anderes die Datei seit unserem letzten Lesen/Schreiben verändert?
+// Nie gelesene Dateien gelten als unverändert.
+pub fn extern_geaendert(pfad: &Path) -> bool {
+    BEKANNT.with(|bekannt| bekannt.borrow().get(pfad).is_some_and(|alt| *alt != stand(pfad)))
+}
+
+fn aenderung(pfad: &Path) -> Option<Aenderung> {
+    let name = pfad.file_name()?.to_str()?;
+    if name == "settings.ini" {
+        Some(Aenderung::Einstellungen)
+    } else if name.ends_with(".list") {
+        Some(Aenderung::Liste(name.to_string()))
+    } else {
+        None
+    }
+}
+
+// Der Rückgabewert muss am Leben bleiben, sonst endet die Überwachung
+pub fn beobachte(
+    ordner: &Path,
+    debug: bool,
+    reaktion: impl Fn(&[Aenderung]) + 'static,
+) -> Option<gio::FileMonitor> {
+    let monitor = match gio::File::for_path(ordner)
+        .monitor_directory(gio::FileMonitorFlags::WATCH_MOVES, gio::Cancellable::NONE)
+    {
+        Ok(monitor) => monitor,
+        Err(e) => {
+            eprintln!("❌ {:?}: {}", ordner, e);
+            return None;
+        }
+    };
+
+    if debug {
+        println!("👀 Watching {:?}", ordner);
+    }
+
+    // Editoren schreiben oft in mehreren Schritten (temporäre Datei, umbenennen) → kurz sammeln
+    let gesammelt: Rc<RefCell<BTreeSet<PathBuf>>> = Rc::default();
+    let reaktion = Rc::new(reaktion);
+
+    monitor.connect_changed(move |_, datei, andere, ereignis| {
+        use gio::FileMonitorEvent::*;
+        if !matches!(ereignis, Changed | ChangesDoneHint | Created | Deleted | Renamed | MovedIn | MovedOut) {
+            return;
+        }
+
+        let geplant = !gesammelt.borrow().is_empty();
+        gesammelt
+            .borrow_mut()
+            .extend([Some(datei), andere].into_iter().flatten().filter_map(|datei| datei.path()));
+        if geplant {
+            return;
+        }
+
+        let gesammelt = Rc::clone(&gesammelt);
+        let reaktion = Rc::clone(&reaktion);
+        glib::timeout_add_local_once(Duration::from_millis(300), move || {
+            let aenderungen: Vec<Aenderung> = gesammelt
+                .take()
+                .into_iter()
+                .filter(|pfad| extern_geaendert(pfad))
+                .filter_map(|pfad| aenderung(&pfad))
+                .collect();
+
+            if debug && !aenderungen.is_empty() {
+                println!("👀 Changed on disk: {:?}", aenderungen);
+            }
+            if !aenderungen.is_empty() {
+                reaktion(&aenderungen);
+            }
+        });
+    });
+
+    Some(monitor)
+}
@@ -1,34 +1,33 @@
-use dbus::blocking::{Connection, Proxy};
+use dbus::blocking::Connection;
 use dbus::channel::MatchingReceiver;
 use dbus::message::MatchRule;
+use dbus::MethodErr;
 use std::time::Duration;
 use std::sync::mpsc::Sender;
 
-// Prüft, ob schon ein Picker läuft.
-// Ruft 'RaiseWindow' per D-Bus auf, wenn erreichbar. Sonst false.
-pub fn pruefe_ob_picker_laeuft() -> bool {
-    if let Ok(conn) = Connection::new_session() {
-        let proxy = Proxy::new(
-            "de.kai_thanner.emoji_picker",
-            "/de/kai_thanner/emoji_picker",
-            Duration::from_millis(500),
-            &conn,
-        );
-        let res = proxy.method_call::<(), (), &str, &str>("de.kai_thanner.emoji_picker", "Quit", ());
-        println!("Proxy-Call result: {:?}", res);
-        res.is_ok()
-    } else {
-        println!("Could not connect to session bus!");
-        false
-    }
+use crate::cli::Startziel;
+
+// Nachrichten an den GTK-Thread (D-Bus-Methoden und Portal-Tastenkürzel)
+#[derive(Debug, Clone)]
+pub enum Anfrage {
+    Schliessen,             // Quit
+    Umschalten,             // Tastenkürzel über das Portal: zeigen oder verstecken
+    Oeffnen(Startziel),     // Show, ShowCategory, Search
 }
 
+// Methoden unter de.kai_thanner.emoji_picker (Busname mit eigenem Konfigurationsordner
+// de.kai_thanner.emoji_picker.k<hash>, siehe pfade::mit_kennung):
+//   Quit()                   Fenster schließen
+//   Show()                   Fenster öffnen bzw. nach vorne holen
+//   ShowCategory(s name)     in einer Kategorie öffnen ("food", "flags", … oder "history")
+//   Search(s text)           mit vorausgefüllter Suche öffnen
+
 // Startet den D-Bus-Service.
-pub fn starte_dbus_service(sender: Sender<&'static str>) {
+pub fn starte_dbus_service(sender: Sender<Anfrage>) {
     use dbus::channel::Sender as DbusSender;
 
     let conn = Connection::new_session().expect("D-Bus Session Connection failed");
-    let result = conn.request_name("de.kai_thanner.emoji_picker", false, true, false);
+    let result = conn.request_name(crate::pfade::mit_kennung("de.kai_thanner.emoji_picker"), false, true, false);
 
     if let Err(e) = result {
         eprintln!("Unable to register D-Bus name: {e}");
@@ -42,14 +41,36 @@ pub fn starte_dbus_service(sender: Sender<&'static str>) {
         println!("Got a D-Bus message: {:?}", msg);
         if let Some(method) = msg.member() {
             println!("Method member: {:?}", method);
-            if method == "Quit".into() {
-                println!("Quit called via D-Bus!");
-                // Nur Signal "show_window" schicken
-                let _ = sender.send("show_window");
-                let reply = msg.method_return();
-                if let Err(e) = DbusSender::send(handler_conn, reply) {
-                    eprintln!("Failed to send D-Bus reply: {:?}", e);
+
+            let anfrage = match &*method {
+                "Quit" => {
+                    println!("Quit called via D-Bus!");
+                    Ok(Anfrage::Schliessen)
+                }
+                "Show" => Ok(Anfrage::Oeffnen(Startziel::default())),
+                "ShowCategory" => match msg.read1::<&str>() {
+                    Ok(name) => match crate::kategorie_seite(name) {
+                        Some(seite) => Ok(Anfrage::Oeffnen(Startziel { seite: Some(seite), suche: None })),
+                        None => Err(MethodErr::invalid_arg(name)),
+                    },
+                    Err(_) => Err(MethodErr::no_arg()),
+                },
+                "Search" => match msg.read1::<&str>() {
+                    Ok(text) => Ok(Anfrage::Oeffnen(Startziel { seite: None, suche: Some(text.to_string()) })),
+                    Err(_) => Err(MethodErr::no_arg()),
+                },
+                andere => Err(MethodErr::no_method(andere)),
+            };
+
+            let reply = match anfrage {
+                Ok(anfrage) => {
+                    let _ = sender.send(anfrage);
+                    msg.method_return()
                 }
+                Err(fehler) => fehler.to_message(&msg),
+            };
+            if let Err(e) = DbusSender::send(handler_conn, reply) {
+                eprintln!("Failed to send D-Bus reply: {:?}", e);
             }
         }
         true
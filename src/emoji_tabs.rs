@@ -11,24 +11,46 @@ use std::{
 };
 
 use crate::{settings::Einstellungen};
+use crate::dateiwaechter;
 use crate::i18n::Sprache;
+use crate::schlagwoerter::{self, Schlagwoerter};
 
 #[derive(Clone)]
 pub struct Symbol {
     pub emoji: String,
     pub begriffe: Vec<String>,
-    pub zaehler: usize
+    pub zaehler: usize,
+    pub lokal: Vec<String>,     // Begriffe in der Sprache der Oberfläche (schlagwoerter.rs), nicht in der .list
+    pub englisch: Vec<String>,  // englische Begriffe aus keywords/en.list bzw. CLDR en.xml (schlagwoerter.rs)
+}
+
+impl Symbol {
+    // Begriffe für Suche und Tooltip: zuerst die der Sprache, dazu die englischen und die
+    // aus der .list, wenn gewünscht oder wenn es für die Sprache keine gibt
+    pub fn suchbegriffe(&self, mit_standard: bool) -> Vec<&str> {
+        let mut begriffe: Vec<&str> = self.lokal.iter().map(String::as_str).collect();
+        if mit_standard || begriffe.is_empty() {
+            for begriff in self.englisch.iter().chain(&self.begriffe) {
+                if !begriffe.contains(&begriff.as_str()) {
+                    begriffe.push(begriff);
+                }
+            }
+        }
+        begriffe
+    }
 }
 
 pub fn erstelle_tabs(
     notebook: &Notebook,
     kategorien: &[(impl AsRef<str>, impl AsRef<str>)],
     emoji_size: i32,
+    schlagwoerter: &Schlagwoerter,
 ) -> HashMap<String, (Vec<Symbol>, Rc<Grid>)> {
     let mut emoji_daten = HashMap::new();
 
     for (datei, label) in kategorien {
-        let emojies = lade_emojies(datei.as_ref());
+        let mut emojies = lade_emojies(datei.as_ref());
+        schlagwoerter::wende_an(&mut emojies, schlagwoerter);
 
         let grid = grid();
 
@@ -67,11 +89,24 @@ pub fn fuege_emojis_ein(
             button.set_hexpand(false);
             button.set_halign(gtk::Align::Center);
 
-            // Tooltip
-            let begriffe = symbol.begriffe.join(", ");
+            // Tooltip: erst beim Anzeigen zusammenstellen, damit Sprachwechsel und
+            // die Einstellung "suche_englisch" ohne Neuaufbau gelten
+            let emoji_tooltip = symbol.emoji.clone();
+            let label_tooltip = label.clone();
+            let emojies_daten_tooltip = Rc::clone(&emojies_daten);
+            let einstellungen_tooltip = Rc::clone(&einstellungen);
             button.set_has_tooltip(true);
             button.connect_query_tooltip(move |_, _, _, _, tooltip| {
-                tooltip.set_text(Some(&begriffe));
+                let Ok(daten) = emojies_daten_tooltip.try_borrow() else {
+                    return false;
+                };
+                let Some(symbol) = daten
+                    .get(&label_tooltip)
+                    .and_then(|(symbole, _)| symbole.iter().find(|s| s.emoji == emoji_tooltip))
+                else {
+                    return false;
+                };
+                tooltip.set_text(Some(&symbol.suchbegriffe(einstellungen_tooltip.suche_englisch.get()).join(", ")));
                 true
             });
 
@@ -128,19 +163,22 @@ pub fn fuege_emojis_ein(
         }
 
         for (i, button) in buttons.into_iter().enumerate() {
-            let row = i / 14;
+            let row = i / 14;     // Spalte 0 = Zeilenanfang, bei RTL rechts
             let col = i % 14;
             grid.attach(&button, col as i32, row as i32, 1, 1);
         }
     }
 }
 
-fn lade_emojies(dateiname: &str) -> Vec<Symbol> {
-    let mut pfad = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-    pfad.push("emoji-picker");
-    pfad.push(dateiname);
-    let inhalt = fs::read_to_string(pfad)        // Datei (pfad) als String lesen
+pub fn listen_pfad(dateiname: &str) -> PathBuf {
+    crate::pfade::konfig_datei(dateiname)
+}
+
+pub fn lade_emojies(dateiname: &str) -> Vec<Symbol> {
+    let pfad = listen_pfad(dateiname);
+    let inhalt = fs::read_to_string(&pfad)       // Datei (pfad) als String lesen
                         .unwrap_or_default();    // Wenn Datei fehlt oder fehlerhaft, ersetze durch leeren String "" - verhindert einen crash
+    dateiwaechter::merke(&pfad);
 
     inhalt
         .lines()                // Zeile für Zeile
@@ -162,7 +200,7 @@ fn lade_emojies(dateiname: &str) -> Vec<Symbol> {
                     begriffe.extend(parts.map(|s| s.to_lowercase()));   // Dahinter alle Begriffe kleingeschrieben
                 }
             }
-            Some(Symbol { emoji, begriffe, zaehler })
+            Some(Symbol { emoji, begriffe, zaehler, lokal: Vec::new(), englisch: Vec::new() })
         }).collect()            // wandelt Some(Symbol) in Vec<Symbol> um
     
 }
@@ -178,25 +216,29 @@ pub fn speichere_kopiere_und_schliesse(
     let mut daten = daten.borrow_mut();
 
     // Entweder direkt via Label (schnell) oder Such-Schleife
-    let eintrag = if let Some(datei) = datei {
-        daten
-            .get_mut(datei)
-            .map(|(symbole, _)| (datei.to_string(), symbole))
-    } else {
-        daten
-            .iter_mut()
+    let datei = match datei {
+        Some(datei) => Some(datei.to_string()),
+        None => daten
+            .iter()
             .find(|(_, (symbole, _))| symbole.iter().any(|s| s.emoji == emoji))
-            .map(|(datei, (symbole, _))| (datei.clone(), symbole))
+            .map(|(datei, _)| datei.clone()),
     };
 
-    if let Some((datei, symbole)) = eintrag {
-        if let Some(s) = symbole.iter_mut().find(|s| s.emoji == emoji) {
+    if let Some(datei) = datei {
+        uebernimm_aenderungen(&mut daten, &datei);
+        if let Some((symbole, _)) = daten.get_mut(&datei)
+            && let Some(s) = symbole.iter_mut().find(|s| s.emoji == emoji)
+        {
             s.zaehler += 1;
-            speichere_emojies(&format!("{}", datei), symbole);
+            speichere_emojies(&datei, symbole);
+            protokolliere_nutzung(emoji);
         }
     }
 
-    if let Some(cb) = clipboard {
+    // Wartet ein Aufruf mit --print, bekommt er das Emoji auf stdout statt in die Zwischenablage
+    let ausgegeben = clipboard.is_some() && crate::aufruf::melde_auswahl(emoji);
+
+    if let Some(cb) = clipboard && !ausgegeben {
         cb.set_text(emoji);
 
         // 📋 Debug-Ausgabe aktiv?
@@ -213,7 +255,7 @@ pub fn speichere_kopiere_und_schliesse(
         
     }
 
-    if schliessen {
+    if schliessen || ausgegeben {
         if let Some(surface) = window.surface() {
             if let Some(gdk_window) = surface.downcast::<Toplevel>().ok() {
                 // gdk_window.minimize();
@@ -225,10 +267,8 @@ pub fn speichere_kopiere_und_schliesse(
     }
 }
 
-fn speichere_emojies(dateiname: &str, symbole: &[Symbol]) {
-    let mut pfad = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-    pfad.push("emoji-picker");
-    pfad.push(dateiname);
+pub fn speichere_emojies(dateiname: &str, symbole: &[Symbol]) {
+    let pfad = listen_pfad(dateiname);
 
     let mut zeilen = Vec::new();
 
@@ -248,16 +288,76 @@ fn speichere_emojies(dateiname: &str, symbole: &[Symbol]) {
     }
 
     let _ = fs::write(&pfad, zeilen.join("\n"));
+    dateiwaechter::merke(&pfad);
+}
+
+// Liest eine .list-Datei erneut ein; die Suchbegriffe aus schlagwoerter.rs bleiben erhalten
+pub fn lade_kategorie_neu(daten: &mut HashMap<String, (Vec<Symbol>, Rc<Grid>)>, dateiname: &str) -> bool {
+    let Some((symbole, _)) = daten.get_mut(dateiname) else {
+        return false;
+    };
+    let mut neu = lade_emojies(dateiname);
+    for symbol in neu.iter_mut() {
+        if let Some(alt) = symbole.iter().find(|alt| alt.emoji == symbol.emoji) {
+            symbol.lokal = alt.lokal.clone();
+            symbol.englisch = alt.englisch.clone();
+        }
+    }
+    *symbole = neu;
+    true
+}
+
+// Vor dem Schreiben: wurde die Datei inzwischen von außen geändert, zuerst deren Stand
+// übernehmen, statt ihn mit dem alten Speicherstand zu überschreiben
+fn uebernimm_aenderungen(daten: &mut HashMap<String, (Vec<Symbol>, Rc<Grid>)>, dateiname: &str) {
+    if dateiname != "🕓" && dateiwaechter::extern_geaendert(&listen_pfad(dateiname)) {
+        eprintln!("🔀 {} was changed on disk, reloading before saving", dateiname);
+        lade_kategorie_neu(daten, dateiname);
+    }
+}
+
+// Nutzungsprotokoll für `emoji-picker stats`: eine Zeile je Auswahl
+// Format: 1760870400 😂      (Unix-Zeit in Sekunden, Emoji)
+//         1760870400 😂 17   (nach dem Zusammenfassen: Anzahl im Monat, letzte Zeit)
+pub fn nutzungsprotokoll() -> PathBuf {
+    crate::pfade::konfig_datei("usage.log")
+}
+
+pub fn protokolliere_nutzung(emoji: &str) {
+    use std::io::Write;
+
+    let sekunden = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|dauer| dauer.as_secs())
+        .unwrap_or(0);
+
+    let pfad = nutzungsprotokoll();
+    let datei = fs::OpenOptions::new().create(true).append(true).open(&pfad);
+    if let Err(e) = datei.and_then(|mut datei| writeln!(datei, "{} {}", sekunden, emoji)) {
+        eprintln!("❌ usage.log: {}", e);
+    }
+
+    // Nicht endlos wachsen lassen: ab der Grenze je Monat und Emoji zusammenfassen
+    if fs::metadata(&pfad).is_ok_and(|daten| daten.len() > crate::statistik::PROTOKOLL_GRENZE)
+        && let Err(e) = crate::statistik::verdichte_protokoll(&pfad)
+    {
+        eprintln!("❌ usage.log: {}", e);
+    }
 }
 
 // Erzeuge aus allen Symbolen die Top-100 History-Liste nach Nutzung
 pub fn generiere_history_kategorie(
     daten: &HashMap<String, (Vec<Symbol>, Rc<Grid>)>,
 ) -> (Vec<Symbol>, Rc<Grid>) {
+    // Neuen Grid erzeugen
+    (history_symbole(daten), grid())
+}
 
-    // Alle Symbole aus allen Kategorien sammeln
+fn history_symbole(daten: &HashMap<String, (Vec<Symbol>, Rc<Grid>)>) -> Vec<Symbol> {
+    // Alle Symbole aus allen Kategorien sammeln (ohne den Verlauf selbst)
     let mut symbole_alle: Vec<Symbol> = daten
         .iter()
+        .filter(|(datei, _)| *datei != "🕓")
         .flat_map(|(_, (symbole, _))| symbole.clone())
         .filter(|s| s.zaehler > 0)
         .collect();
@@ -265,11 +365,30 @@ pub fn generiere_history_kategorie(
     // Nach Nutzung sortieren (absteigend) und auf 100 begrenzen
     symbole_alle.sort_by(|a, b| b.zaehler.cmp(&a.zaehler));
     symbole_alle.truncate(100);
+    symbole_alle
+}
 
-    // Neuen Grid erzeugen
-    let grid = grid();
-
-    (symbole_alle, grid)
+// Alle Grids samt Verlauf aus den Daten neu aufbauen, z.B. nachdem .list-Dateien
+// von außen geändert wurden. Die Grids selbst (und damit die Notebook-Seiten) bleiben.
+pub fn baue_grids_neu(
+    emojies_daten: Rc<RefCell<HashMap<String, (Vec<Symbol>, Rc<Grid>)>>>,
+    clipboard: Rc<Clipboard>,
+    window: Rc<ApplicationWindow>,
+    einstellungen: Rc<Einstellungen>,
+) {
+    {
+        let mut daten = emojies_daten.borrow_mut();
+        let verlauf = history_symbole(&daten);
+        if let Some((symbole, _)) = daten.get_mut("🕓") {
+            *symbole = verlauf;
+        }
+        for (_, grid) in daten.values() {
+            while let Some(child) = grid.first_child() {
+                grid.remove(&child);
+            }
+        }
+    }
+    fuege_emojis_ein(emojies_daten, clipboard, window, einstellungen);
 }
 
 fn grid() -> Rc<Grid> {
@@ -283,37 +402,44 @@ fn grid() -> Rc<Grid> {
     grid
 }
 
+const EINGEBETTETES_CSS: &str = include_str!("../assets/usr/share/emoji-picker/emoji-picker.css");
+
 pub fn lade_ui_css(sprachpaket: Rc<Sprache>, debug: bool) {
 	let global_css = CssProvider::new();
-    let css_pfade = if cfg!(debug_assertions) {
-        vec![   // nur für Entwicklung
-            PathBuf::from(format!("../assets/usr/share/emoji-picker/emoji-picker.css")),    // // start aus emoji-picker/src/
-            PathBuf::from(format!("./assets/usr/share/emoji-picker/emoji-picker.css")),     // start aus emoji-picker/
-        ]
-    } else {
-        vec![  
-            PathBuf::from(format!("/usr/share/emoji-picker/emoji-picker.css"))
-        ]
-    };
+    let css_pfade: Vec<PathBuf> = crate::pfade::daten_ordner()
+        .into_iter()
+        .map(|ordner| ordner.join("emoji-picker.css"))
+        .collect();
+
+    let gefunden = css_pfade.iter().find(|pfad| {
+        let vorhanden = fs::metadata(pfad).is_ok();
+        if !vorhanden && debug {
+            eprintln!("🚫 {}: {:?}", sprachpaket.debug_emoji_tabs_css_failure, pfad);
+        }
+        vorhanden
+    });
 
-    for pfad in &css_pfade {
-        if fs::metadata(pfad).is_ok() {
+    match gefunden {
+        Some(pfad) => {
             global_css.load_from_path(pfad);
-            gtk::style_context_add_provider_for_display(
-                &gdk::Display::default().unwrap(),
-                &global_css,
-                STYLE_PROVIDER_PRIORITY_APPLICATION,
-            );
             if debug {
                 println!("📤 {}: {:?}", sprachpaket.debug_emoji_tabs_use_css, pfad);
             }
-            break;
-        } else {
+        }
+        None => {
+            // Mitgeliefertes Stylesheet aus dem Programm, wenn keine Datei installiert ist
+            global_css.load_from_data(EINGEBETTETES_CSS);
             if debug {
-                eprintln!("🚫 {}: {:?}", sprachpaket.debug_emoji_tabs_css_failure, pfad);
+                println!("📦 {}: built-in", sprachpaket.debug_emoji_tabs_use_css);
             }
         }
     }
+
+    gtk::style_context_add_provider_for_display(
+        &gdk::Display::default().unwrap(),
+        &global_css,
+        STYLE_PROVIDER_PRIORITY_APPLICATION,
+    );
 }
 
 pub fn aktualisiere_tablabel_style(emoji_size: i32) {
@@ -352,6 +478,12 @@ pub fn leere_history_tab(
             grid.remove(&child);
         }
 
+        // Von außen geänderte Listen erst einlesen, damit deren Änderungen erhalten bleiben
+        let dateinamen: Vec<String> = emojies_daten.keys().cloned().collect();
+        for dateiname in dateinamen {
+            uebernimm_aenderungen(&mut emojies_daten, &dateiname);
+        }
+
         // Alle Zähler zurücksetzen und speichern
         for (dateiname, (symbole, _)) in emojies_daten.iter_mut() {
             if dateiname != "🕓" {
@@ -1,20 +1,19 @@
 use std::{
     rc::Rc,
-    process::Command,
-    fs,
     path::Path,
 };
 
 use crate::shortcut::{detect_desktop, Desktop};
 use crate::i18n::Sprache;
+use crate::umgebung::Umgebung;
 
-pub fn pruefe_und_setze_gtk_theme_fuer_kde(sprachpaket: Rc<Sprache>, debug: bool) {
-    if !matches!(detect_desktop(), Desktop::Kde) {
+pub fn pruefe_und_setze_gtk_theme_fuer_kde(umgebung: &dyn Umgebung, sprachpaket: Rc<Sprache>, debug: bool) {
+    if !matches!(detect_desktop(umgebung), Desktop::Kde) {
         return;
     }
 
-    if let Some(theme_basis) = ermittle_kde_theme(Rc::clone(&sprachpaket), debug) {
-        let gtk_theme = finde_kde_gtk_theme_schreibweise(&theme_basis, Rc::clone(&sprachpaket), debug);
+    if let Some(theme_basis) = ermittle_kde_theme(umgebung, Rc::clone(&sprachpaket), debug) {
+        let gtk_theme = finde_kde_gtk_theme_schreibweise(umgebung, &theme_basis, Rc::clone(&sprachpaket), debug);
 
         let theme_name = match gtk_theme {
             Some(ref korrekt)   => {
@@ -49,13 +48,12 @@ pub fn pruefe_und_setze_gtk_theme_fuer_kde(sprachpaket: Rc<Sprache>, debug: bool
     }
 }
 
-fn ermittle_kde_theme(sprachpaket: Rc<Sprache>, debug: bool) -> Option<String> {
-    let ausgabe = Command::new("kreadconfig5")
-        .args(&["--group", "Icons", "--key", "Theme"])
-        .output()
+fn ermittle_kde_theme(umgebung: &dyn Umgebung, sprachpaket: Rc<Sprache>, debug: bool) -> Option<String> {
+    let ausgabe = umgebung
+        .abfrage("kreadconfig5", &["--group", "Icons", "--key", "Theme"])
         .ok()?;
 
-    let theme = String::from_utf8_lossy(&ausgabe.stdout).trim().to_string();
+    let theme = ausgabe.stdout.trim().to_string();
     
     if debug {
         println!("{} >> {}", sprachpaket.debug_gtk_theme_kde_determinded_theme, theme);
@@ -68,12 +66,10 @@ fn ermittle_kde_theme(sprachpaket: Rc<Sprache>, debug: bool) -> Option<String> {
     }
 }
 
-fn finde_kde_gtk_theme_schreibweise(basisname: &str, sprachpaket: Rc<Sprache>, debug: bool) -> Option<String> {
+fn finde_kde_gtk_theme_schreibweise(umgebung: &dyn Umgebung, basisname: &str, sprachpaket: Rc<Sprache>, debug: bool) -> Option<String> {
     let theme_dir = "/usr/share/themes";
-    let dirs = fs::read_dir(theme_dir).ok()?;
 
-    for dir in dirs.flatten() {
-        let name = dir.file_name().to_string_lossy().to_string();
+    for name in umgebung.ordnerinhalt(Path::new(theme_dir)) {
 
         if debug {
             println!("{} >> {}", sprachpaket.debug_gtk_theme_kde_theme_folder, name);
@@ -83,7 +79,7 @@ fn finde_kde_gtk_theme_schreibweise(basisname: &str, sprachpaket: Rc<Sprache>, d
             // z.B. breeze-dark -> Breeze-Dark
             let theme_path = format!("{theme_dir}/{}/gtk-4.0", name);
 
-            if Path::new(&theme_path).exists() {
+            if umgebung.existiert(Path::new(&theme_path)) {
                 return Some(name); // das korrekt geschriebene Theme
             }
         }
@@ -1,12 +1,18 @@
 // 	Internationalization - I18n
 
 use serde::Deserialize;
-use std::{fs, path::PathBuf};
+use serde_json::{Map, Value};
+use std::{cell::RefCell, collections::HashMap, fs, path::PathBuf, rc::Rc};
 
 #[derive(Deserialize, Clone, Debug)]
 pub struct Sprache {
+	#[serde(skip)]
+	pub code: String,			// tatsächlich geladene Sprache, z.B. "pt-BR" (wird nicht aus der Datei gelesen)
 	pub title: String,
+	pub direction: String,			// "ltr" oder "rtl", Leserichtung der Oberfläche
 	pub settings_window: String,
+	pub settings_problems: String,
+	pub settings_save_failed: String,
 	pub setup_done: String,
 	pub setup_done_cinna: String,
 	pub setup_fail: String,
@@ -14,20 +20,79 @@ pub struct Sprache {
 	pub setup_not_available: String,
 	pub set_desk_unknown: String,
 	pub setup_exists: String,
+	pub setup_removed: String,
+	pub setup_none: String,
+	pub setup_conflict: String,
+	pub setup_no_free_slot: String,
 	pub setup_done_xfce_gno: String,
 	pub setup_fail_xfce_1: String,
 	pub setup_fail_xfce_2: String,
+	pub setup_done_portal: String,
+	pub setup_fail_portal: String,
+	pub setup_portal_cli: String,
+	pub setup_done_kde: String,
+	pub setup_done_kde_file: String,
+	pub setup_done_lxqt_file: String,
+	pub setup_done_wm: String,
+	pub setup_wm_restart: String,
+	pub setup_wm_snippet: String,
+	pub setup_wm_question: String,
+	pub setup_wm_append: String,
+	pub setup_wm_show: String,
 	pub search_placeholder: String,
 	pub set_key: String,
 	pub set_key_tooltip: String,
+	pub hotkey_label: String,
+	pub hotkey_capture: String,
 	pub close_window_get: String,
 	pub close_window_dnd: String,
+	pub search_english: String,
 	pub emoji_size: String,
 	pub hist_reset: String,
 	pub label_language: String,
 	pub button_cancel: String,
 	pub button_ok: String,
-	pub restart_after_change: String,
+
+	pub cli_usage: String,
+	pub cli_options: String,
+	pub cli_commands: String,
+	pub cli_license: String,
+	pub cli_help_help: String,
+	pub cli_help_version: String,
+	pub cli_help_lang: String,
+	pub cli_help_debug: String,
+	pub cli_help_config_dir: String,
+	pub cli_help_portable: String,
+	pub cli_help_query: String,
+	pub cli_help_category: String,
+	pub cli_help_print: String,
+	pub cli_help_dmenu: String,
+	pub cli_help_select: String,
+	pub cli_help_type: String,
+	pub cli_help_setup: String,
+	pub cli_help_search: String,
+	pub cli_help_limit: String,
+	pub cli_help_json: String,
+	pub cli_help_pick: String,
+	pub cli_help_shortcut: String,
+	pub cli_help_dry_run: String,
+	pub cli_help_completions: String,
+	pub cli_help_stats: String,
+	pub cli_help_top: String,
+	pub cli_help_by_category: String,
+	pub cli_help_format: String,
+	pub cli_help_i18n: String,
+	pub cli_error_unknown_option: String,
+	pub cli_error_missing_value: String,
+	pub cli_error_invalid_value: String,
+	pub cli_error_unexpected: String,
+	pub cli_error_hint: String,
+	pub stats_top: String,
+	pub stats_categories: String,
+	pub stats_months: String,
+	pub stats_total: String,
+	pub stats_none: String,
+	pub stats_no_timestamps: String,
 
 	pub debug_main_time_loading_language: String,
 	pub debug_main_list_fail_to_copy: String,
@@ -50,6 +115,8 @@ pub struct Sprache {
 	pub debug_shortcut_set_info_window: String,
 	pub debug_shortcut_apply_gsettings_error: String,
 	pub debug_shortcut_cinna_already_done: String,
+	pub debug_shortcut_already_done: String,
+	pub debug_shortcut_apply_error: String,
 
 	pub debug_gtk_theme_kde_gtk_theme: String,
 	pub debug_gtk_theme_kde_gtk_fallback: String,
@@ -60,6 +127,34 @@ pub struct Sprache {
 	pub debug_gtk_theme_kde_darkmode_aktiv: String,
 }
 
+// Mitgelieferte Sprachdateien im Programm selbst – letzte Schicht, falls /usr/share fehlt
+// (cargo install, Tarball, falsches Arbeitsverzeichnis). Dateien auf der Platte haben Vorrang.
+const EINGEBETTETE_SPRACHEN: &[(&str, &str)] = &[
+	("ar",    include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.ar.json")),
+	("da-DK", include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.da-DK.json")),
+	("de",    include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.de.json")),
+	("en-US", include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.en-US.json")),
+	("en",    include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.en.json")),
+	("es",    include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.es.json")),
+	("fi",    include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.fi.json")),
+	("fr",    include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.fr.json")),
+	("it",    include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.it.json")),
+	("ja-JP", include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.ja-JP.json")),
+	("nb-NO", include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.nb-NO.json")),
+	("nl",    include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.nl.json")),
+	("pl",    include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.pl.json")),
+	("pt-BR", include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.pt-BR.json")),
+	("pt",    include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.pt.json")),
+	("ru",    include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.ru.json")),
+	("sv",    include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.sv.json")),
+	("tr",    include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.tr.json")),
+	("uk-UA", include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.uk-UA.json")),
+	("zh-CN", include_str!("../assets/usr/share/emoji-picker/locale/emoji-picker.zh-CN.json")),
+];
+
+// Technische Werte statt Text – bleiben in jeder Sprache wie im Englischen
+const NICHT_UEBERSETZT: &[&str] = &["direction"];
+
 pub struct VerfuegbareSprache {
 	pub code: String,		// z.B. "de"
 	pub flagge: String,		// z.B. "🇩🇪"
@@ -67,16 +162,16 @@ pub struct VerfuegbareSprache {
 }
 
 impl Sprache {
+	// Ordner mit Sprachdateien, wichtigster zuerst (siehe pfade.rs):
+	//   ~/.config/emoji-picker/locale/            eigene Übersetzungen und Korrekturen (ohne root)
+	//   $XDG_DATA_HOME/emoji-picker/locale/       z.B. ~/.local/share
+	//   [Entwicklung: assets/ im Repository]
+	//   $XDG_DATA_DIRS/emoji-picker/locale/       Standard: /usr/local/share, /usr/share
 	fn pfad_sprachdateien() -> Vec<String> {
-		let json_pfade = if cfg!(debug_assertions) {
-			vec![
-				"../assets/usr/share/emoji-picker/locale/".to_string(),	// start aus emoji-picker/src/
-				"./assets/usr/share/emoji-picker/locale/".to_string(),	// start aus emoji-picker/
-			]
-		} else {
-			vec!["/usr/share/emoji-picker/locale/".to_string()]			// start nach installation
-		};
-		json_pfade
+		std::iter::once(crate::pfade::konfig_datei("locale"))
+			.chain(crate::pfade::daten_ordner().into_iter().map(|ordner| ordner.join("locale")))
+			.map(|ordner| format!("{}/", ordner.display()))
+			.collect()
 	}
 
 	// Liste verfügbarer Sprachcodes + Flaggen + Namen
@@ -101,7 +196,7 @@ impl Sprache {
 					let dateiname = entry.file_name().to_string_lossy().to_string();
 
 					if let Some(code) = dateiname.strip_prefix("emoji-picker.").and_then(|s| s.strip_suffix(".json")) {
-						if code == "system" { continue; } // vermeiden
+						if code == "system" || sprachen.iter().any(|sprache| sprache.code == code) { continue; } // vermeiden
 						let flagge = Self::flagge_fuer_code(code);
 						let name = Self::name_fuer_code(code);
 						sprachen.push(VerfuegbareSprache {
@@ -114,6 +209,17 @@ impl Sprache {
 			}
 		}
 
+		// Eingebettete Sprachen, für die keine Datei gefunden wurde
+		for (code, _) in EINGEBETTETE_SPRACHEN {
+			if !sprachen.iter().any(|sprache| sprache.code == *code) {
+				sprachen.push(VerfuegbareSprache {
+					code: code.to_string(),
+					flagge: Self::flagge_fuer_code(code),
+					name: Self::name_fuer_code(code),
+				});
+			}
+		}
+
 		sprachen.sort_by(|a, b| {
 			if a.code == "system" {
 				std::cmp::Ordering::Less
@@ -178,53 +284,162 @@ impl Sprache {
 		}.into()
 	}
 
-	pub fn lade_sprache(codes: &[impl AsRef<str>], debug: bool) -> Self {
+	// Feldnamen direkt aus #[derive(Deserialize)] – serde übergibt sie an deserialize_struct
+	pub fn felder() -> &'static [&'static str] {
+		use serde::de::{self, Visitor};
 
-		for code in codes {
-			let code = code.as_ref();
-			let dateiname = format!("emoji-picker.{}.json", code);
-			let json_pfade = Self::pfad_sprachdateien();
+		struct FeldSammler<'a>(&'a mut &'static [&'static str]);
+
+		impl<'de> serde::Deserializer<'de> for FeldSammler<'_> {
+			type Error = de::value::Error;
+
+			fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+				Err(de::Error::custom("nur Feldnamen"))
+			}
+
+			fn deserialize_struct<V: Visitor<'de>>(
+				self,
+				_name: &'static str,
+				fields: &'static [&'static str],
+				_visitor: V,
+			) -> Result<V::Value, Self::Error> {
+				*self.0 = fields;
+				Err(de::Error::custom("nur Feldnamen"))
+			}
 
-			if debug {
-				println!("📤 Loading language file '{}'", dateiname);
+			serde::forward_to_deserialize_any! {
+				bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+				option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any
 			}
+		}
 
-			for pfad in json_pfade {
-				let dateipfad = PathBuf::from(format!("{}{}", pfad, dateiname));
-				if dateipfad.exists() {
-					if let Ok(inhalt) = fs::read_to_string(&dateipfad) {
-						if let Ok(parsed) = serde_json::from_str::<Sprache>(&inhalt) {
-	                        if debug {
-	                            println!("📂 Language file loaded from: {:?}\n🔀 Switching to '{}'", dateipfad, code);
-	                        }
-	                        return parsed;
-	                    } else if debug {
-	                    	println!("❌ Failed to parse language file: {:?}", dateipfad);
-	                    }
+		let mut felder: &'static [&'static str] = &[];
+		let _ = Sprache::deserialize(FeldSammler(&mut felder));
+		felder
+	}
+
+	// Alle Dateien einer Sprache als Schlüssel → Text. Wichtigere Ordner überschreiben einzelne
+	// Schlüssel; ganz unten liegt die eingebaute Kopie. Eine Datei in ~/.config mit nur
+	// "button_ok" ändert also nur diesen Text.
+	fn lade_sprachdatei(code: &str, debug: bool) -> Option<Map<String, Value>> {
+		let dateiname = format!("emoji-picker.{}.json", code);
+
+		if debug {
+			println!("📤 Loading language file '{}'", dateiname);
+		}
+
+		let mut texte = EINGEBETTETE_SPRACHEN
+			.iter()
+			.find(|(eingebettet, _)| *eingebettet == code)
+			.and_then(|(_, inhalt)| serde_json::from_str::<Map<String, Value>>(inhalt).ok());
+
+		if debug && texte.is_some() {
+			println!("📦 Built-in copy of '{}'", dateiname);
+		}
+
+		for pfad in Self::pfad_sprachdateien().iter().rev() {
+			let dateipfad = PathBuf::from(format!("{}{}", pfad, dateiname));
+			let Ok(inhalt) = fs::read_to_string(&dateipfad) else {
+				continue;
+			};
+			match serde_json::from_str::<Map<String, Value>>(&inhalt) {
+				Ok(schluessel) => {
+					if debug {
+						println!("📂 Language file loaded from: {:?} ({} keys)", dateipfad, schluessel.len());
 					}
+					texte.get_or_insert_with(Map::new).extend(schluessel);
+				}
+				Err(e) => {
+					if debug {
+						println!("❌ Failed to parse language file {:?}: {}", dateipfad, e);
+					}
+				}
+			}
+		}
+
+		if debug && texte.is_none() {
+			println!("🚫 Language file '{}' not found.", dateiname);
+		}
+		texte
+	}
+
+	// Schichten je Schlüssel: gewünschte Sprache → Grundsprache → Englisch, z.B. pt-BR → pt → en.
+	// Fehlt einer Sprachdatei ein Schlüssel, gilt nur für diesen die nächste Schicht.
+	pub fn lade_sprache(codes: &[impl AsRef<str>], debug: bool) -> Self {
+		let kette = Self::sprachkette(codes);
+		let (texte, aktiv, ersetzt) = Self::schichte(&kette, |code| Self::lade_sprachdatei(code, debug));
+
+		if debug {
+			println!("🔀 Switching to '{}' (layers: {})", aktiv, kette.join(" → "));
+			if !ersetzt.is_empty() {
+				println!("⚠️  '{}' is missing {} keys, using fallback:", aktiv, ersetzt.len());
+				for eintrag in &ersetzt {
+					println!("     {}", eintrag);
 				}
 			}
+		}
+
+		let mut sprache: Sprache = serde_json::from_value(Value::Object(texte))
+			.unwrap_or_else(|e| panic!("🚫 Language '{}' could not be loaded: {}", aktiv, e));
+		sprache.code = aktiv;
+		sprache
+	}
 
-			if debug {
-            	println!("🚫 Language file '{}' not found.", dateiname);
-        	}
+	// ["pt-BR"] → ["pt-BR", "pt", "en"]
+	fn sprachkette(codes: &[impl AsRef<str>]) -> Vec<String> {
+		let mut kette: Vec<String> = Vec::new();
+		for code in codes {
+			let code = code.as_ref();
+			let grundsprache = code.split(['-', '_']).next().unwrap_or(code);
+			for eintrag in [code, grundsprache] {
+				if !kette.iter().any(|vorhanden| vorhanden == eintrag) {
+					kette.push(eintrag.to_string());
+				}
+			}
 		}
+		if !kette.iter().any(|code| code == "en") {
+			kette.push("en".to_string());
+		}
+		kette
+	}
 
-	    // ⛑️ Fallback auf Englisch (fest definierter Pfad in assets)
-	    let json_pfade = Self::pfad_sprachdateien();
-	    for pfad in json_pfade {
-		    let fallback = PathBuf::from(format!("{}emoji-picker.en.json", pfad));
-		    if let Ok(backup) = fs::read_to_string(&fallback) {
-		        if let Ok(parsed) = serde_json::from_str::<Sprache>(&backup) {
-		            if debug {
-		                println!("🚫 No language file found – using fallback (English UK)");
-		            }
-		            return parsed;
-		        }
-		    }
+	// Legt die Schichten von hinten nach vorne übereinander. Ergebnis: Texte, erste gefundene
+	// Sprache der Kette und die Schlüssel, die aus einer späteren Schicht kommen ("title (pt)")
+	fn schichte(kette: &[String], lade_schicht: impl Fn(&str) -> Option<Map<String, Value>>) -> (Map<String, Value>, String, Vec<String>) {
+		let mut texte: Map<String, Value> = Map::new();
+		let mut herkunft: HashMap<String, String> = HashMap::new();
+		let mut aktiv = None;		// erste gefundene Sprache der Kette
+
+		for code in kette.iter().rev() {
+			let Some(schicht) = lade_schicht(code) else {
+				continue;
+			};
+			for (schluessel, text) in schicht {
+				// Leere Texte gelten als nicht übersetzt (z.B. aus `i18n template`)
+				if text.as_str().is_some_and(|text| !text.is_empty()) {
+					herkunft.insert(schluessel.clone(), code.clone());
+					texte.insert(schluessel, text);
+				}
+			}
+			aktiv = Some(code.clone());
 		}
 
-	    panic!("🚫 No languagefile found – fallback failed.");
+		// Englisch ist eingebettet, die Kette endet also nie leer
+		let aktiv = aktiv.unwrap_or_else(|| "en".to_string());
+
+		// Schlüssel, die keine Schicht kennt, zeigen ihren Namen statt die Oberfläche zu verlieren
+		let mut ersetzt = Vec::new();
+		for feld in Self::felder() {
+			match herkunft.get(*feld) {
+				Some(code) if *code == aktiv => {}
+				Some(code) => ersetzt.push(format!("{} ({})", feld, code)),
+				None => {
+					texte.insert(feld.to_string(), Value::String(feld.to_string()));
+					ersetzt.push(format!("{} (–)", feld));
+				}
+			}
+		}
+		(texte, aktiv, ersetzt)
 	}
 
 	pub fn sprache_erkennen(code: &Option<String>, debug: bool) -> Self {
@@ -232,47 +447,323 @@ impl Sprache {
 		match code {
 			Some(inner) => Self::lade_sprache(&[inner], debug),
 			None       => {
-	            let mut pfad = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-	            pfad.push("emoji-picker/settings.ini");
-
-	            if pfad.exists() {
-	                if let Ok(content) = std::fs::read_to_string(&pfad) {
-	                    for line in content.lines() {
-	                        if line.trim().starts_with("sprache") {
-	                            if let Some(value) = line.split('=').nth(1) {
-	                                let code = value.trim();
-	                                if debug {
-	                                    println!("🌍 Detected language from settings.ini: {}", code);
-	                                }
-	                                if code != "system" {
-	                                	return Self::lade_sprache(&[code], debug);	
-	                                }
-	                            }
-	                        }
-	                    }
-	                }
-	            }
-	            // Systemsprache laden
-			    let lang_env = std::env::var("LANG").unwrap_or_else(|_| "en".into());
-			    
-			    // lang_env → z.B. "de_DE.UTF-8" ➡️ "de_DE"
-			    let lang_region = lang_env.split('.').next().unwrap_or("en");	// z.B. "de-AT"
-			    let parts: Vec<&str> = lang_region.split(['_']).collect();
-			    let lang = parts.get(0).unwrap_or(&"en");						// "de"
-			    let region = parts.get(1);										// "AT"
-
-			    let lang_code = if let Some(region) = region {
-			    	vec![format!("{}-{}", lang, region), lang.to_string()]
-			    } else {
-			    	vec![lang.to_string()]
-			    };
-
+				// Nur lesen, die Datei legt erst das Fenster an (nicht schon --help oder completions)
+				let sprache = crate::settings::lies_settings().sprache.into_inner();
 				if debug {
-					println!("🌍 Detected system language: '{:?}'", lang_code);
+					println!("🌍 Detected language from settings.ini: {}", sprache);
+				}
+				Self::aus_auswahl(&sprache, debug)
+			}
+		}
+	}
+
+	// Auswahl wie in settings.ini: "system" oder ein Sprachcode
+	pub fn aus_auswahl(auswahl: &str, debug: bool) -> Self {
+		if auswahl == "system" {
+			Self::systemsprache(debug)
+		} else {
+			Self::lade_sprache(&[auswahl], debug)
+		}
+	}
+
+	fn systemsprache(debug: bool) -> Self {
+		let lang_env = std::env::var("LANG").unwrap_or_else(|_| "en".into());
+
+		// lang_env → z.B. "de_DE.UTF-8" ➡️ "de_DE"
+		let lang_region = lang_env.split('.').next().unwrap_or("en");	// z.B. "de-AT"
+		let parts: Vec<&str> = lang_region.split(['_']).collect();
+		let lang = parts.get(0).unwrap_or(&"en");						// "de"
+		let region = parts.get(1);										// "AT"
+
+		let lang_code = if let Some(region) = region {
+			vec![format!("{}-{}", lang, region), lang.to_string()]
+		} else {
+			vec![lang.to_string()]
+		};
+
+		if debug {
+			println!("🌍 Detected system language: '{:?}'", lang_code);
+		}
+
+		Self::lade_sprache(&lang_code, debug)
+	}
+
+	// "rtl" z.B. für Arabisch, alles andere (auch ein leerer Wert) gilt als links → rechts
+	pub fn rechts_nach_links(&self) -> bool {
+		self.direction.trim().eq_ignore_ascii_case("rtl")
+	}
+
+	// ─── emoji-picker i18n check | template ─────────────────────────
+
+	// Geprüft wird gegen Sprache::felder(), also immer gegen den aktuellen Stand des Structs.
+	//   fehlend         Feld ohne Schlüssel in der Datei        → Exit-Code 1
+	//   überzählig      Schlüssel, den kein Feld mehr kennt     → Exit-Code 1
+	//   unübersetzt     leer oder wörtlich wie Englisch         (Hinweis, Varianten von "en" und NICHT_UEBERSETZT ausgenommen)
+	// Eine Datei in ~/.config/emoji-picker/locale/ zu einer installierten Sprache überschreibt
+	// nur einzelne Texte: dort zählen nur überzählige Schlüssel als Fehler, leere als Hinweis.
+	pub fn pruefe_uebersetzungen(nur: Option<&str>, debug: bool) -> i32 {
+		let felder = Self::felder();
+		let englisch = Self::lade_sprachdatei("en", debug).unwrap_or_default();
+
+		// Jede Datei für sich: eingebettete Kopie und andere Ordner verdecken sonst Lücken
+		let dateien = Self::sprachdateien_auf_platte(nur);
+		if dateien.is_empty() {
+			println!("❌ {:<7} language file not found", nur.unwrap_or("*"));
+			return 1;
+		}
+
+		let mut fehlerhaft = 0;
+		for (code, pfad, ueberschreibt) in &dateien {
+			let datei = match fs::read_to_string(pfad).map_err(|e| e.to_string()).and_then(|inhalt| {
+				serde_json::from_str::<Map<String, Value>>(&inhalt).map_err(|e| e.to_string())
+			}) {
+				Ok(datei) => datei,
+				Err(e) => {
+					println!("❌ {:<7} {}: {}", code, pfad.display(), e);
+					fehlerhaft += 1;
+					continue;
+				}
+			};
+
+			let englische_variante = code.split(['-', '_']).next() == Some("en");
+
+			let ueberzaehlig: Vec<&str> = datei.keys().map(String::as_str).filter(|schluessel| !felder.contains(schluessel)).collect();
+
+			if *ueberschreibt {
+				let leer: Vec<&str> = datei
+					.iter()
+					.filter(|(schluessel, text)| felder.contains(&schluessel.as_str()) && text.as_str() == Some(""))
+					.map(|(schluessel, _)| schluessel.as_str())
+					.collect();
+				let symbol = if !ueberzaehlig.is_empty() {
+					fehlerhaft += 1;
+					"❌"
+				} else if !leer.is_empty() {
+					"⚠️ "
+				} else {
+					"✅"
+				};
+				println!("{} {:<7} {} keys (override)  {}", symbol, code, datei.len(), pfad.display());
+				for (art, schluessel) in [("extra", &ueberzaehlig), ("empty", &leer)] {
+					if !schluessel.is_empty() {
+						println!("           {} {}: {}", schluessel.len(), art, schluessel.join(", "));
+					}
+				}
+				continue;
+			}
+
+			let fehlend: Vec<&str> = felder.iter().copied().filter(|feld| !datei.contains_key(*feld)).collect();
+			let unuebersetzt: Vec<&str> = felder
+				.iter()
+				.copied()
+				.filter(|feld| !NICHT_UEBERSETZT.contains(feld))
+				.filter(|feld| match datei.get(*feld).and_then(Value::as_str) {
+					Some(text) => text.is_empty() || (!englische_variante && englisch.get(*feld).and_then(Value::as_str) == Some(text)),
+					None => false,
+				})
+				.collect();
+
+			let symbol = if !fehlend.is_empty() || !ueberzaehlig.is_empty() {
+				fehlerhaft += 1;
+				"❌"
+			} else if !unuebersetzt.is_empty() {
+				"⚠️ "
+			} else {
+				"✅"
+			};
+			println!("{} {:<7} {}/{} keys  {}", symbol, code, felder.len() - fehlend.len(), felder.len(), pfad.display());
+
+			for (art, schluessel) in [("missing", &fehlend), ("extra", &ueberzaehlig), ("untranslated", &unuebersetzt)] {
+				if !schluessel.is_empty() {
+					println!("           {} {}: {}", schluessel.len(), art, schluessel.join(", "));
 				}
+			}
+		}
+
+		if fehlerhaft > 0 { 1 } else { 0 }
+	}
+
+	// Sprachdateien in allen Ordnern, wichtigster Ordner zuerst (optional nur ein Sprachcode).
+	// true = Überschreibung: im Konfigurationsordner, die Sprache ist aber auch installiert.
+	fn sprachdateien_auf_platte(nur: Option<&str>) -> Vec<(String, PathBuf, bool)> {
+		let mut dateien = Vec::new();
+		for (stelle, ordner) in Self::pfad_sprachdateien().into_iter().enumerate() {
+			let Ok(eintraege) = fs::read_dir(&ordner) else {
+				continue;
+			};
+			let mut gefunden: Vec<(String, PathBuf)> = eintraege
+				.flatten()
+				.filter_map(|eintrag| {
+					let dateiname = eintrag.file_name().to_string_lossy().to_string();
+					let code = dateiname.strip_prefix("emoji-picker.")?.strip_suffix(".json")?.to_string();
+					Some((code, eintrag.path()))
+				})
+				.filter(|(code, _)| nur.is_none_or(|nur| nur == code))
+				.collect();
+			gefunden.sort();
+			dateien.extend(gefunden.into_iter().map(|(code, pfad)| (code, pfad, stelle == 0)));
+		}
 
-				Self::lade_sprache(&lang_code, debug)
+		let installiert: Vec<String> = dateien
+			.iter()
+			.filter(|(_, _, konfig)| !konfig)
+			.map(|(code, _, _)| code.clone())
+			.chain(EINGEBETTETE_SPRACHEN.iter().map(|(code, _)| code.to_string()))
+			.collect();
+		for (code, _, ueberschreibt) in dateien.iter_mut() {
+			*ueberschreibt = *ueberschreibt && installiert.contains(code);
+		}
+		dateien
+	}
+
+	// Gerüst für eine neue Sprache: alle Felder in Struct-Reihenfolge, vorbelegt mit dem englischen Text
+	pub fn schreibe_vorlage(code: &str, debug: bool) -> i32 {
+		let dateiname = format!("emoji-picker.{}.json", code);
+		let pfad = PathBuf::from(&dateiname);
+		if pfad.exists() {
+			eprintln!("❌ {:?} already exists", pfad);
+			return 1;
+		}
+
+		let englisch = Self::lade_sprachdatei("en", debug).unwrap_or_default();
+		let felder = Self::felder();
+		let breite = felder.iter().map(|feld| feld.len()).max().unwrap_or(0) + 4;
+
+		let zeilen: Vec<String> = felder
+			.iter()
+			.map(|feld| {
+				let text = englisch.get(*feld).and_then(Value::as_str).unwrap_or("");
+				let schluessel = format!("\"{}\":", feld);
+				format!("  {:<breite$}{}", schluessel, Value::String(text.to_string()))
+			})
+			.collect();
+
+		match fs::write(&pfad, format!("{{\n{}\n}}\n", zeilen.join(",\n"))) {
+			Ok(_) => {
+				println!("✅ {} ({} keys, English text as placeholder)", dateiname, felder.len());
+				println!("   Translate the texts and move it to {}/,", crate::pfade::konfig_datei("locale").display());
+				println!("   then check with: emoji-picker i18n check {}", code);
+				0
+			}
+			Err(e) => {
+				eprintln!("❌ {:?}: {}", pfad, e);
+				1
 			}
 		}
 	}
-}
\ No newline at end of file
+}
+
+// ╔══════════════════════════════════════════════════════════════╗
+// ║             Sprachwechsel im laufenden Programm              ║
+// ╚══════════════════════════════════════════════════════════════╝
+//
+//   AktiveSprache::neu(sprache)
+//         │
+//         ├─► beschrifte(|s| suchfeld.set_placeholder_text(…))   sofort ausgeführt und gemerkt
+//         │
+//         ▼
+//   wechsle("fr")  ──► Sprache::aus_auswahl()  ──► alle Beschriftungen erneut ausführen
+//
+// Dialoge holen sich beim Öffnen aktuell() und erscheinen damit schon in der neuen Sprache.
+// Eine Beschriftung darf selbst weder beschrifte() noch wechsle() aufrufen.
+type Beschriftung = Box<dyn Fn(&Sprache)>;
+
+pub struct AktiveSprache {
+	sprache: RefCell<Rc<Sprache>>,
+	beschriftungen: RefCell<Vec<Beschriftung>>,
+}
+
+impl AktiveSprache {
+	pub fn neu(sprache: Rc<Sprache>) -> Rc<Self> {
+		Rc::new(Self {
+			sprache: RefCell::new(sprache),
+			beschriftungen: RefCell::new(Vec::new()),
+		})
+	}
+
+	pub fn aktuell(&self) -> Rc<Sprache> {
+		Rc::clone(&self.sprache.borrow())
+	}
+
+	pub fn beschrifte(&self, beschriftung: impl Fn(&Sprache) + 'static) {
+		beschriftung(&self.aktuell());
+		self.beschriftungen.borrow_mut().push(Box::new(beschriftung));
+	}
+
+	// auswahl wie in settings.ini: "system" oder ein Sprachcode
+	pub fn wechsle(&self, auswahl: &str, debug: bool) {
+		let neu = Rc::new(Sprache::aus_auswahl(auswahl, debug));
+		if debug {
+			println!("🌍 Switching language at runtime: {} → {}", self.aktuell().code, neu.code);
+		}
+		self.sprache.replace(Rc::clone(&neu));
+
+		for beschriftung in self.beschriftungen.borrow().iter() {
+			beschriftung(&neu);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn eingebettet(code: &str) -> Map<String, Value> {
+		let (_, inhalt) = EINGEBETTETE_SPRACHEN.iter().find(|(eingebettet, _)| *eingebettet == code).unwrap();
+		serde_json::from_str(inhalt).unwrap()
+	}
+
+	fn teilweise(texte: &[(&str, &str)]) -> Map<String, Value> {
+		texte.iter().map(|(schluessel, text)| (schluessel.to_string(), Value::String(text.to_string()))).collect()
+	}
+
+	#[test]
+	fn kette_mit_grundsprache_und_englisch() {
+		assert_eq!(Sprache::sprachkette(&["pt-BR"]), ["pt-BR", "pt", "en"]);
+		assert_eq!(Sprache::sprachkette(&["de-AT", "de"]), ["de-AT", "de", "en"]);
+		assert_eq!(Sprache::sprachkette(&["en-US"]), ["en-US", "en"]);
+	}
+
+	#[test]
+	fn schluessel_fallen_einzeln_zurueck() {
+		let mut englisch = eingebettet("en");
+		englisch.remove("button_cancel");
+		let pt = teilweise(&[("button_ok", "Confirmar"), ("title", "Seletor de emojis")]);
+		// Leerer Text (z.B. aus `i18n template`) zählt als fehlend
+		let pt_br = teilweise(&[("button_ok", "Beleza"), ("title", "")]);
+
+		let kette = Sprache::sprachkette(&["pt-BR"]);
+		let (texte, aktiv, ersetzt) = Sprache::schichte(&kette, |code| match code {
+			"pt-BR" => Some(pt_br.clone()),
+			"pt" => Some(pt.clone()),
+			"en" => Some(englisch.clone()),
+			_ => None,
+		});
+
+		assert_eq!(aktiv, "pt-BR");
+		assert_eq!(texte["button_ok"], "Beleza");
+		assert_eq!(texte["title"], "Seletor de emojis");
+		assert_eq!(texte["search_placeholder"], englisch["search_placeholder"]);
+		assert_eq!(texte["button_cancel"], "button_cancel");
+
+		assert!(!ersetzt.iter().any(|eintrag| eintrag.starts_with("button_ok ")));
+		assert!(ersetzt.contains(&"title (pt)".to_string()));
+		assert!(ersetzt.contains(&"search_placeholder (en)".to_string()));
+		assert!(ersetzt.contains(&"button_cancel (–)".to_string()));
+		assert_eq!(ersetzt.len(), Sprache::felder().len() - 1);
+
+		// Alle Felder belegt: daraus wird eine vollständige Sprache
+		let sprache: Sprache = serde_json::from_value(Value::Object(texte)).unwrap();
+		assert_eq!(sprache.button_ok, "Beleza");
+	}
+
+	#[test]
+	fn fehlende_sprache_faellt_auf_englisch() {
+		let kette = Sprache::sprachkette(&["eo"]);
+		let (texte, aktiv, ersetzt) = Sprache::schichte(&kette, |code| (code == "en").then(|| eingebettet("en")));
+
+		assert_eq!(aktiv, "en");
+		assert!(ersetzt.is_empty());
+		assert_eq!(texte["button_ok"], eingebettet("en")["button_ok"]);
+	}
+}
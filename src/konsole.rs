@@ -0,0 +1,295 @@
+use serde::Serialize;
+use std::{
+    fs::OpenOptions,
+    io::{self, BufRead, IsTerminal, Write},
+    process::{Command, Stdio},
+    rc::Rc,
+};
+
+use crate::cli::CliFehler;
+use crate::emoji_tabs::{self, Symbol};
+use crate::i18n::Sprache;
+use crate::{schlagwoerter, settings, suchlogik};
+
+// ╔══════════════════════════════════════════════════════════════╗
+// ║   emoji-picker search | pick | --dmenu  (ohne GTK-Fenster)   ║
+// ╚══════════════════════════════════════════════════════════════╝
+//
+//   search <Wörter> [--limit N] [--json]
+//         │
+//         ├─► *.list aus ~/.config/emoji-picker (wie im Fenster)
+//         ├─► suchlogik::passt()               gleiche Treffer wie das Suchfeld
+//         └─► stdout: "😀<TAB>begriffe …" je Zeile, oder JSON-Liste
+//
+//   pick <Emoji>
+//         │
+//         ├─► Zähler in der .list erhöhen      → 🕓-Verlauf im Fenster
+//         └─► Zwischenablage: wl-copy ─► xclip ─► xsel ─► OSC 52 (Terminal, auch über SSH)
+//
+//   --dmenu                              Liste für rofi, wofi, fuzzel, fzf
+//         └─► stdout: alle Emojis als "😀<TAB>begriffe …", 🕓-Verlauf zuerst
+//
+//   --dmenu --select [--type]            gewählte Zeile von stdin zurück
+//         └─► wie pick, mit --type zusätzlich eintippen: wtype ─► xdotool ─► ydotool
+//
+// Exit-Codes: 0 = ok, 1 = nichts gefunden / nicht kopiert, 2 = falscher Aufruf
+
+#[derive(Serialize)]
+struct Treffer<'a> {
+    emoji: &'a str,
+    keywords: Vec<&'a str>,
+    category: &'a str,
+    count: usize,
+}
+
+// Alle Kategorien in fester Reihenfolge laden, legt fehlende .list-Dateien aus /etc an.
+// Die Suchbegriffe der Sprache kommen wie im Fenster dazu.
+pub fn lade_kategorien(sprachpaket: Rc<Sprache>, debug: bool) -> Vec<(&'static str, Vec<Symbol>)> {
+    let schlagwoerter = schlagwoerter::lade(&sprachpaket.code, debug);
+    crate::KATEGORIEN
+        .iter()
+        .map(|(datei, _)| {
+            crate::kopiere_von_etc_falls_fehlend(datei, Rc::clone(&sprachpaket), &debug);
+            let mut symbole = emoji_tabs::lade_emojies(datei);
+            schlagwoerter::wende_an(&mut symbole, &schlagwoerter);
+            (*datei, symbole)
+        })
+        .collect()
+}
+
+// Einstellung aus settings.ini: Begriffe der .list zusätzlich zur Sprache durchsuchen
+fn mit_standard() -> bool {
+    settings::lies_settings().suche_englisch.get()
+}
+
+pub fn such_befehl(begriffe: &[String], limit: Option<usize>, json: bool, sprachpaket: Rc<Sprache>, debug: bool) -> i32 {
+    let filter_text = begriffe.join(" ").trim().to_lowercase();
+    if filter_text.is_empty() {
+        eprintln!("{}", CliFehler::FehlenderWert("search".into()).meldung(&sprachpaket));
+        return 2;
+    }
+
+    let kategorien = lade_kategorien(sprachpaket, debug);
+    let mit_standard = mit_standard();
+    let treffer: Vec<Treffer> = kategorien
+        .iter()
+        .flat_map(|(datei, symbole)| symbole.iter().map(move |symbol| (*datei, symbol)))
+        .filter(|(_, symbol)| suchlogik::passt(symbol, &filter_text, mit_standard))
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|(datei, symbol)| Treffer {
+            emoji: &symbol.emoji,
+            keywords: symbol.suchbegriffe(mit_standard),
+            category: datei.strip_suffix(".list").unwrap_or(datei),
+            count: symbol.zaehler,
+        })
+        .collect();
+
+    if debug {
+        eprintln!("🔍 '{}': {} matches", filter_text, treffer.len());
+    }
+
+    if json {
+        match serde_json::to_string_pretty(&treffer) {
+            Ok(text) => println!("{}", text),
+            Err(e) => {
+                eprintln!("❌ JSON: {}", e);
+                return 1;
+            }
+        }
+    } else {
+        for eintrag in &treffer {
+            println!("{}\t{}", eintrag.emoji, eintrag.keywords.join(" "));
+        }
+    }
+
+    if treffer.is_empty() { 1 } else { 0 }
+}
+
+pub fn waehl_befehl(emoji: &str, sprachpaket: Rc<Sprache>, debug: bool) -> i32 {
+    waehle(emoji, false, sprachpaket, debug)
+}
+
+// --dmenu: Liste ausgeben, mit --select die Auswahl des Launchers von stdin übernehmen
+pub fn dmenu_befehl(auswaehlen: bool, tippen: bool, sprachpaket: Rc<Sprache>, debug: bool) -> i32 {
+    if !auswaehlen {
+        let mit_standard = mit_standard();
+        for symbol in verlauf_zuerst(lade_kategorien(sprachpaket, debug)) {
+            println!("{}\t{}", symbol.emoji, symbol.suchbegriffe(mit_standard).join(" "));
+        }
+        return 0;
+    }
+
+    // rofi & Co. geben die ganze Zeile zurück, das Emoji steht vor dem Tab
+    let zeile = io::stdin()
+        .lock()
+        .lines()
+        .map_while(Result::ok)
+        .find(|zeile| !zeile.trim().is_empty());
+
+    match zeile.as_deref().and_then(|zeile| zeile.split(['\t', ' ']).find(|teil| !teil.is_empty())) {
+        Some(emoji) => waehle(emoji, tippen, sprachpaket, debug),
+        None => 1,  // Launcher abgebrochen
+    }
+}
+
+// Benutzte Emojis nach Häufigkeit (wie der 🕓-Tab), danach alle übrigen in Kategorie-Reihenfolge
+fn verlauf_zuerst(kategorien: Vec<(&'static str, Vec<Symbol>)>) -> Vec<Symbol> {
+    let mut symbole: Vec<Symbol> = kategorien.into_iter().flat_map(|(_, symbole)| symbole).collect();
+    symbole.sort_by_key(|s| std::cmp::Reverse(s.zaehler));     // stabil: Reihenfolge bei 0 bleibt
+    symbole
+}
+
+// Gemeinsamer Abschluss von pick und --dmenu --select
+fn waehle(emoji: &str, tippen: bool, sprachpaket: Rc<Sprache>, debug: bool) -> i32 {
+    let emoji = match zaehle_nutzung(emoji, sprachpaket, debug) {
+        Some(gefunden) => gefunden,
+        None => {
+            eprintln!("⚠️  '{}' is not in the emoji lists, usage not recorded", emoji);
+            emoji.to_string()
+        }
+    };
+
+    let mut code = match kopiere_in_zwischenablage(&emoji) {
+        Ok(werkzeug) => {
+            if debug {
+                eprintln!("📋 Copied '{}' via {}", emoji, werkzeug);
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("❌ Clipboard: {}", e);
+            1
+        }
+    };
+
+    if tippen {
+        match tippe(&emoji) {
+            Ok(werkzeug) => {
+                if debug {
+                    eprintln!("⌨️  Typed '{}' via {}", emoji, werkzeug);
+                }
+            }
+            Err(e) => {
+                eprintln!("❌ Type: {}", e);
+                code = 1;
+            }
+        }
+    }
+    code
+}
+
+// Variantenauswahl (U+FE0F) ignorieren, damit "❤" und "❤️" dasselbe Emoji treffen
+pub fn ohne_variante(text: &str) -> String {
+    text.replace('\u{FE0F}', "")
+}
+
+// Erhöht den Zähler wie ein Klick im Fenster, liefert das Emoji in der Schreibweise der Liste
+fn zaehle_nutzung(emoji: &str, sprachpaket: Rc<Sprache>, debug: bool) -> Option<String> {
+    let gesucht = ohne_variante(emoji);
+
+    for (datei, mut symbole) in lade_kategorien(sprachpaket, debug) {
+        if let Some(symbol) = symbole.iter_mut().find(|s| ohne_variante(&s.emoji) == gesucht) {
+            symbol.zaehler += 1;
+            let gefunden = symbol.emoji.clone();
+            emoji_tabs::speichere_emojies(datei, &symbole);
+            emoji_tabs::protokolliere_nutzung(&gefunden);
+            return Some(gefunden);
+        }
+    }
+    None
+}
+
+// Versucht nacheinander Wayland, X11 und zuletzt das Terminal (OSC 52), liefert das genutzte Werkzeug
+pub fn kopiere_in_zwischenablage(text: &str) -> io::Result<&'static str> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let x11 = std::env::var_os("DISPLAY").is_some();
+
+    let werkzeuge: [(bool, &'static str, &[&str]); 3] = [
+        (wayland, "wl-copy", &[]),
+        (x11,     "xclip",   &["-selection", "clipboard"]),
+        (x11,     "xsel",    &["--clipboard", "--input"]),
+    ];
+
+    for (verfuegbar, befehl, args) in werkzeuge {
+        if verfuegbar && schreibe_an_befehl(befehl, args, text).is_ok() {
+            return Ok(befehl);
+        }
+    }
+
+    kopiere_per_osc52(text).map(|_| "OSC 52")
+}
+
+// Tippt den Text in das fokussierte Fenster, liefert das genutzte Werkzeug
+fn tippe(text: &str) -> io::Result<&'static str> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let x11 = std::env::var_os("DISPLAY").is_some();
+
+    let werkzeuge: [(bool, &'static str, &[&str]); 3] = [
+        (wayland, "wtype",   &["--"]),
+        (x11,     "xdotool", &["type", "--clearmodifiers", "--"]),
+        (true,    "ydotool", &["type", "--"]),      // uinput, unabhängig von der Sitzung
+    ];
+
+    for (verfuegbar, befehl, args) in werkzeuge {
+        if !verfuegbar {
+            continue;
+        }
+        if let Ok(status) = Command::new(befehl).args(args).arg(text).stderr(Stdio::null()).status()
+            && status.success()
+        {
+            return Ok(befehl);
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::NotFound, "no typing tool (wtype, xdotool, ydotool) available"))
+}
+
+// Text über stdin übergeben – wl-copy, xclip und xsel bleiben danach selbst im Hintergrund
+fn schreibe_an_befehl(befehl: &str, args: &[&str], text: &str) -> io::Result<()> {
+    let mut prozess = Command::new(befehl)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = prozess.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+
+    let status = prozess.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("{} exited with {}", befehl, status)))
+    }
+}
+
+// Escape-Sequenz direkt an das Terminal, stdout bleibt für Pipes frei
+fn kopiere_per_osc52(text: &str) -> io::Result<()> {
+    let sequenz = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+
+    match OpenOptions::new().write(true).open("/dev/tty") {
+        Ok(mut terminal) => terminal.write_all(sequenz.as_bytes()),
+        Err(_) if io::stderr().is_terminal() => io::stderr().write_all(sequenz.as_bytes()),
+        Err(e) => Err(io::Error::new(e.kind(), "no clipboard tool (wl-copy, xclip, xsel) and no terminal")),
+    }
+}
+
+fn base64(daten: &[u8]) -> String {
+    const ZEICHEN: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut ausgabe = String::with_capacity(daten.len().div_ceil(3) * 4);
+    for block in daten.chunks(3) {
+        let b = [block[0], *block.get(1).unwrap_or(&0), *block.get(2).unwrap_or(&0)];
+        let wert = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+
+        for i in 0..4 {
+            if i <= block.len() {
+                ausgabe.push(ZEICHEN[(wert >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                ausgabe.push('=');
+            }
+        }
+    }
+    ausgabe
+}
@@ -1,10 +1,21 @@
+mod aufruf;
+mod cli;
+mod dateiwaechter;
 mod dbus_api;
 mod emoji_tabs;
 mod gtk_theme;
 mod i18n;
+mod konsole;
+mod pfade;
+mod portal;
+mod schlagwoerter;
 mod settings;
 mod shortcut;
+mod statistik;
 mod suchlogik;
+mod tastenkombi;
+mod umgebung;
+mod vervollstaendigung;
 
 use gtk::prelude::*;
 use gtk::{
@@ -12,46 +23,66 @@ use gtk::{
     EventControllerKey, Grid, Notebook, Orientation, PolicyType, ScrolledWindow, Stack,
 };
 use gtk::gdk;
+use gtk::gio::{ApplicationCommandLine, ApplicationFlags};
 use glib::clone;
 use glib::ControlFlow::Continue;
 use std::{
     cell::RefCell,
     fs::self,
-    path::PathBuf,
     rc::Rc,
     sync::{Arc, Mutex, mpsc::{channel, Sender, Receiver}},
     time::{Instant, SystemTime},
 };
 
-use crate::i18n::Sprache;
-use dbus_api::{ pruefe_ob_picker_laeuft, starte_dbus_service };
+use crate::cli::{Aufruf, Befehl, Startoptionen, Startziel, UebersetzungAktion};
+use crate::i18n::{AktiveSprache, Sprache};
+use crate::dateiwaechter::Aenderung;
+use crate::umgebung::Umgebung;
+use dbus_api::{Anfrage, starte_dbus_service};
+
+// Kategorien: Listendatei + Tab-Symbol
+const KATEGORIEN: [(&str, &str); 12] = [
+    ("smileys.list",    "😄"),
+    ("peoples.list",    "👨"),
+    ("animals.list",    "🐰"),
+    ("gestures.list",   "👋"),
+    ("clothing.list",   "👕"),
+    ("activity.list",   "🏀"),
+    ("travel.list",     "✈️"),
+    ("nature.list",     "🌲"),
+    ("food.list",       "🍌"),
+    ("objects.list",    "📎"),
+    ("symbole.list",    "✅"),
+    ("flags.list",      "🇩🇪"),
+];
+
+// Widgets, auf die spätere Aufrufe (weitergeleitete Kommandozeilen) zugreifen
+struct Hauptfenster {
+    window: Rc<ApplicationWindow>,
+    suchfeld: Entry,
+    notebook: Rc<Notebook>,
+    _dateiwaechter: Option<gtk::gio::FileMonitor>,     // überwacht den Konfigurationsordner, solange das Fenster lebt
+}
 
-fn main() {
-    pruefe_ob_picker_laeuft();
-    
-    let (dbus_tx, dbus_rx): (Sender<&'static str>, Receiver<&'static str>) = channel();
+fn main() -> glib::ExitCode {
+    let (dbus_tx, dbus_rx): (Sender<Anfrage>, Receiver<Anfrage>) = channel();
     let dbus_rx = Arc::new(Mutex::new(dbus_rx));
 
-    std::thread::spawn(move || {
-        starte_dbus_service(dbus_tx);
-    });
-
     // Zeitmessung für Programmstart
     let args: Vec<String> = std::env::args().collect();
-    let debug: bool = if args.contains(&"--debug".to_string()) { true } else { false };
+    let aufruf = Aufruf::aus_argumenten(&args);
+    let debug: bool = aufruf.debug;
     let timer = Instant::now();
 
     if debug {
         println!("⏳ Debug output enabled, startup time {:?}", timer.elapsed());
     }
 
+    // Konfigurationsordner festlegen, bevor Sprache oder Einstellungen gelesen werden
+    pfade::lege_fest(aufruf.konfig_ordner.as_deref(), aufruf.portabel, debug);
+
     // Argument --lang abfangen
-    let mut sprachcode: Option<String> = None;
-    for i in 0..args.len() {
-        if args[i] == "--lang" && i + 1 < args.len() {
-            sprachcode = Some(args[i + 1].clone());
-        }
-    }
+    let sprachcode: Option<String> = aufruf.sprache;
 
     // Sprachpaket laden
     let sprachpaket = Rc::new(Sprache::sprache_erkennen(&sprachcode, debug));
@@ -60,55 +91,169 @@ fn main() {
         println!("⏳ {} {:?}", sprachpaket.debug_main_time_loading_language, timer.elapsed());
     }
 
-    // Argumente abfangen
-    if args.contains(&"--setup".to_string()) || args.contains(&"-S".to_string()) {
-        shortcut::setup_shortcut(Rc::clone(&sprachpaket), debug);
-        return;
-    }
+    // Befehle ohne Fenster erledigen, nur Befehl::Fenster startet GTK
+    let befehl = match aufruf.befehl {
+        Ok(befehl) => befehl,
+        Err(fehler) => {
+            eprintln!("{}", fehler.meldung(&sprachpaket));
+            return glib::ExitCode::from(2);
+        }
+    };
 
-    if args.contains(&"--version".to_string()) || args.contains(&"-V".to_string()) {
-        println!("Emoji Picker 📦 Version: {}", env!("CARGO_PKG_VERSION"));
-        println!("Copyright © 2025");
-        println!("Lizenz: MIT"); 
-        println!("Geschrieben von: {}", env!("CARGO_PKG_AUTHORS")); 
-        std::process::exit(0);
+    let code = match befehl {
+        Befehl::Fenster(_) => None,
+        Befehl::Hilfe => {
+            println!("{}", cli::hilfe(&sprachpaket));
+            Some(0)
+        }
+        Befehl::Version => {
+            println!("{}", cli::version(&sprachpaket));
+            Some(0)
+        }
+        Befehl::Vervollstaendigung(shell) => {
+            print!("{}", vervollstaendigung::skript(shell, &sprachpaket));
+            Some(0)
+        }
+        Befehl::Shortcut { aktion, trocken } => Some(shortcut::shortcut_befehl(&umgebung::System, aktion, trocken, Rc::clone(&sprachpaket), debug)),
+        Befehl::Suche { begriffe, limit, json } => Some(konsole::such_befehl(&begriffe, limit, json, Rc::clone(&sprachpaket), debug)),
+        Befehl::Waehlen(emoji) => Some(konsole::waehl_befehl(&emoji, Rc::clone(&sprachpaket), debug)),
+        Befehl::Dmenu { auswaehlen, tippen } => Some(konsole::dmenu_befehl(auswaehlen, tippen, Rc::clone(&sprachpaket), debug)),
+        Befehl::Statistik { top, nach_kategorie, format } => Some(statistik::statistik_befehl(top, nach_kategorie, format, Rc::clone(&sprachpaket), debug)),
+        Befehl::Uebersetzung(UebersetzungAktion::Pruefen(code)) => Some(Sprache::pruefe_uebersetzungen(code.as_deref(), debug)),
+        Befehl::Uebersetzung(UebersetzungAktion::Vorlage(code)) => Some(Sprache::schreibe_vorlage(&code, debug)),
+    };
+    if let Some(code) = code {
+        return glib::ExitCode::from(code);
     }
 
-    if args.contains(&"--help".to_string()) || args.contains(&"-h".to_string()) {
-        println!("\nUsage: emoji-picker [OPTIONS]");        
-        println!("\nOptions:\n");        
-        println!("-h,  --help              Print help");        
-        println!("-V,  --version           Print version info and exit");
-        println!("-S   --setup             Try to set keybinding");
-        println!("     --debug             Enable debug output");
-        std::process::exit(0);
-    }
+    crate::gtk_theme::pruefe_und_setze_gtk_theme_fuer_kde(&umgebung::System, Rc::clone(&sprachpaket), debug);
 
-    crate::gtk_theme::pruefe_und_setze_gtk_theme_fuer_kde(Rc::clone(&sprachpaket), debug);
+    // Ab hier lässt sich die Sprache im Betrieb wechseln (Einstellungen, weiterer Aufruf mit --lang)
+    let aktive_sprache = AktiveSprache::neu(Rc::clone(&sprachpaket));
 
+    // Eigener Konfigurationsordner → eigene Instanz (siehe pfade::mit_kennung)
     let app: Application = Application::builder()
-        .application_id("de.kai_thanner.emoji-picker")
+        .application_id(pfade::mit_kennung("de.kai_thanner.emoji-picker"))
+        .flags(ApplicationFlags::HANDLES_COMMAND_LINE)      // Argumente weiterer Aufrufe landen in der laufenden Instanz
         .build();
 
+    // D-Bus-Dienst nur in der primären Instanz starten
+    app.connect_startup(move |_| {
+        portal::setze_aktivierung(dbus_tx.clone());
+
+        // Über das Portal eingerichtetes Tastenkürzel erneut anmelden (gilt nur pro Sitzung)
+        if portal::gespeicherte_sitzung().is_some() {
+            let portal_tx = dbus_tx.clone();
+            let trigger = tastenkombi::aus_einstellung(&settings::lies_settings().tastenkombi.borrow()).portal();
+            std::thread::spawn(move || {
+                let conn = match umgebung::System.sitzungsbus() {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        eprintln!("❌ Portal: D-Bus: {}", e);
+                        return;
+                    }
+                };
+                let (rueckmeldung_tx, rueckmeldung_rx) = channel();
+                portal::starte_portal_dienst(conn, Some(portal_tx), rueckmeldung_tx, trigger, debug);
+
+                // Kehrt nur zurück, wenn die Einrichtung fehlschlägt oder die Verbindung abbricht
+                if let Ok(Err(e)) = rueckmeldung_rx.try_recv() {
+                    eprintln!("❌ Portal: {}", e);
+                }
+            });
+        }
+
+        let dbus_tx = dbus_tx.clone();
+        std::thread::spawn(move || {
+            starte_dbus_service(dbus_tx);
+        });
+    });
+
+    let hauptfenster: Rc<RefCell<Option<Hauptfenster>>> = Rc::new(RefCell::new(None));
+
+    // Kategorie/Suche für das nächste activate (von Kommandozeile oder D-Bus)
+    let startziel: Rc<RefCell<Option<Startziel>>> = Rc::new(RefCell::new(None));
+
+    {
+        let hauptfenster = Rc::clone(&hauptfenster);
+        let startziel = Rc::clone(&startziel);
+        let aktive_sprache = Rc::clone(&aktive_sprache);
+        app.connect_command_line(move |app, cmdline| {
+            verarbeite_aufruf(app, cmdline, &hauptfenster, &startziel, &aktive_sprache, debug)
+        });
+    }
+
     app.connect_activate(move |app| {
+        // Fenster existiert bereits → Startziel anwenden und nach vorne holen
+        if let Some(vorhanden) = hauptfenster.borrow().as_ref() {
+            if let Some(ziel) = startziel.take() {
+                zeige_startziel(vorhanden, ziel);
+            }
+            vorhanden.window.present();
+            return;
+        }
+
+        // Leserichtung der Sprache für alle Widgets und Dialoge (vor dem ersten Widget setzen).
+        // Bei RTL spiegelt GTK selbst: Boxen füllen von rechts (Zahnrad links vom Suchfeld),
+        // Grid-Spalte 0 liegt rechts, Notebook-Tabs "Left" erscheinen rechts.
+        // Bei einem Sprachwechsel spiegeln sich auch bereits angezeigte Widgets mit.
+        aktive_sprache.beschrifte(|sprache| {
+            gtk::Widget::set_default_direction(if sprache.rechts_nach_links() {
+                gtk::TextDirection::Rtl
+            } else {
+                gtk::TextDirection::Ltr
+            });
+        });
+
         // Fenster erstellen
         let window = Rc::new(ApplicationWindow::builder()
             .application(app)
-            .title(&sprachpaket.title)//.title("Emoji-Auswahl")
             .default_width(400)
             .default_height(400)
             .build()
         );
+        {
+            let window = Rc::clone(&window);
+            aktive_sprache.beschrifte(move |sprache| window.set_title(Some(&sprache.title)));
+        }
+
+        // Schließen über den Fenstermanager beendet ein wartendes --print ohne Auswahl.
+        // Hängt ein Portal-Tastenkürzel am Prozess, wird das Fenster nur versteckt – das gilt
+        // damit auch für jedes andere window.close().
+        window.connect_close_request(|window| {
+            if portal::aktive_sitzung().is_some() {
+                schliesse_fenster(window);
+                return gtk::glib::Propagation::Stop;
+            }
+            aufruf::melde_abbruch();
+            gtk::glib::Propagation::Proceed
+        });
 
         // Channel im GTK-Thread empfangen!
         let win_dbus = window.clone();
+        let app_dbus = app.clone();
+        let startziel_dbus = Rc::clone(&startziel);
         let dbus_rx_check = dbus_rx.clone();
         glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
-            if let Ok(msg) = dbus_rx_check.lock().unwrap().try_recv() {
-                if msg == "show_window" {
-                    // win_dbus.present();
-                    win_dbus.close();
+            let anfrage = dbus_rx_check.lock().unwrap().try_recv();
+            match anfrage {
+                Ok(Anfrage::Schliessen) => {
+                    schliesse_fenster(&win_dbus);
+                }
+                Ok(Anfrage::Umschalten) => {
+                    // Tastenkürzel über das Portal: Prozess muss weiterlaufen, daher nur verstecken
+                    if win_dbus.is_visible() {
+                        schliesse_fenster(&win_dbus);
+                    } else {
+                        win_dbus.present();
+                    }
                 }
+                Ok(Anfrage::Oeffnen(ziel)) => {
+                    // Gleicher Weg wie ein weiterer Programmaufruf mit --category / --query
+                    startziel_dbus.replace(Some(ziel));
+                    app_dbus.activate();
+                }
+                Err(_) => {}
             }
             Continue
         });
@@ -130,13 +275,19 @@ fn main() {
         // Suchfeld
         let suchfeld = Entry::new();
         suchfeld.add_css_class("search-entry");
-        suchfeld.set_placeholder_text(Some(&sprachpaket.search_placeholder));
+        {
+            let suchfeld = suchfeld.clone();
+            aktive_sprache.beschrifte(move |sprache| suchfeld.set_placeholder_text(Some(&sprache.search_placeholder)));
+        }
         suchfeld.set_hexpand(true); // expandiert innerhalb der Zeile
         suchbox.append(&suchfeld);
 
         // Zahnrad-Button (Oder was der Desktop vorgibt)
         let settings_button = Button::from_icon_name("emblem-system-symbolic");
-        settings_button.set_tooltip_text(Some(&sprachpaket.settings_window));
+        {
+            let settings_button = settings_button.clone();
+            aktive_sprache.beschrifte(move |sprache| settings_button.set_tooltip_text(Some(&sprache.settings_window)));
+        }
         settings_button.set_margin_end(6);
         settings_button.set_margin_top(6);
         settings_button.set_size_request(28, 28);
@@ -159,7 +310,7 @@ fn main() {
 
         // Notebook für Kategorien
         let notebook = Rc::new(Notebook::new());
-        notebook.set_tab_pos(gtk::PositionType::Left);
+        notebook.set_tab_pos(gtk::PositionType::Left);     // Anfang der Zeile: bei RTL rechts
         notebook.set_vexpand(true);
         notebook.set_hexpand(true);
         
@@ -187,37 +338,24 @@ fn main() {
         stack.set_visible_child_name("notebook");
         vbox.append(&stack);
 
-        // Kategorien
-        let kategorien = vec![
-            ("smileys.list",    "😄"),
-            ("peoples.list",    "👨"),
-            ("animals.list",    "🐰"),
-            ("gestures.list",   "👋"),
-            ("clothing.list",   "👕"),
-            ("activity.list",   "🏀"),
-            ("travel.list",     "✈️"),
-            ("nature.list",     "🌲"),
-            ("food.list",       "🍌"),
-            ("objects.list",    "📎"),
-            ("symbole.list",    "✅"),
-            ("flags.list",      "🇩🇪"),
-        ];
-
         // Clipboard vorbereiten
         let display = gtk::gdk::Display::default().unwrap();
         let clipboard = Rc::new(display.clipboard());
 
-        for (datei, _) in &kategorien {
+        for (datei, _) in &KATEGORIEN {
             // .list Dateien anlegen falls nicht vorhanden
             kopiere_von_etc_falls_fehlend(datei, Rc::clone(&sprachpaket), &debug);
         }
 
         if debug {
-            println!("⏳ /etc/emoji-picker {} {:?}", sprachpaket.debug_main_time_copy_from_etc, timer.elapsed());
+            println!("⏳ {} {:?}", sprachpaket.debug_main_time_copy_from_etc, timer.elapsed());
         }
 
+        // Suchbegriffe in der Sprache der Oberfläche
+        let schlagwoerter = schlagwoerter::lade(&sprachpaket.code, debug);
+
         // Symbole parallel Laden
-        let emojies_daten = Rc::new(RefCell::new(emoji_tabs::erstelle_tabs(&notebook, &kategorien[..], emoji_size)));
+        let emojies_daten = Rc::new(RefCell::new(emoji_tabs::erstelle_tabs(&notebook, &KATEGORIEN[..], emoji_size, &schlagwoerter)));
 
         // Nachträglich: History generieren (nachdem alles geladen ist)
         let (history_symbole, history_grid) = emoji_tabs::generiere_history_kategorie(&emojies_daten.borrow());
@@ -240,29 +378,22 @@ fn main() {
             let einstellungen_settings_button = Rc::clone(&einstellungen);
             let window_settings_button = Rc::clone(&window);
             let emojies_daten_settings_button = Rc::clone(&emojies_daten);
-            let sprachpaket_settings_button = Rc::clone(&sprachpaket);
-
-            let window_settings_button_2 = Rc::clone(&window);
-            let sprachpaket_settings_button_2 = Rc::clone(&sprachpaket);
+            let aktive_sprache_settings_button = Rc::clone(&aktive_sprache);
 
+            // Ein Sprachwechsel im Dialog beschriftet das Fenster direkt neu (AktiveSprache)
             settings_button.connect_clicked(move |_| {
-                let sprache_vor_einstellungen = einstellungen_settings_button.sprache.borrow().clone();
-
                 settings::zeige_einstellungsfenster(
                     Rc::clone(&window_settings_button),
                     Rc::clone(&einstellungen_settings_button),
                     Rc::clone(&emojies_daten_settings_button),
-                    Rc::clone(&sprachpaket_settings_button),
+                    Rc::clone(&aktive_sprache_settings_button),
                     debug,
                  );
-                settings::speichere_settings(&einstellungen_settings_button);
-                
-                let sprache_nach_einstellungen = einstellungen_settings_button.sprache.borrow();
-
-                if *sprache_nach_einstellungen != sprache_vor_einstellungen {
-                    neustart(
-                        Rc::clone(&window_settings_button_2),
-                        Rc::clone(&sprachpaket_settings_button_2),
+                if let Err(e) = settings::speichere_settings(&einstellungen_settings_button) {
+                    settings::zeige_warnung(
+                        &window_settings_button,
+                        &aktive_sprache_settings_button.aktuell().settings_save_failed,
+                        &e.to_string(),
                     );
                 }
             });
@@ -274,14 +405,7 @@ fn main() {
 
 
         // Suchindex erstellen (flache Liste aller Symbole)
-        let such_index = Rc::new(
-            emojies_daten
-                .borrow()
-                .iter()
-                .filter(|(label, _)| *label != "🕓")        // History nicht durchsuchen   
-                .flat_map(|(_, symbole)| symbole.0.clone()) // .0 ist Vec<Symbol>, .1 wäre Rc<Grid>
-                .collect::<Vec<_>>()
-        );
+        let such_index = Rc::new(RefCell::new(suchlogik::erstelle_index(&emojies_daten.borrow())));
 
         if debug {
             println!("⏳ {}: {:?}", sprachpaket.debug_main_time_searchindex, timer.elapsed());
@@ -315,6 +439,84 @@ fn main() {
             println!("⏳ {} {:?}", sprachpaket.debug_main_time_to_searchfield, timer.elapsed());
         }
 
+        // Sprachwechsel: Suchbegriffe der neuen Sprache in Tabs und Suchindex übernehmen,
+        // eine laufende Suche mit den neuen Begriffen wiederholen. Tooltips lesen sie beim Anzeigen.
+        {
+            let emojies_daten = Rc::clone(&emojies_daten);
+            let such_index = Rc::clone(&such_index);
+            let suchfeld = suchfeld.clone();
+            let geladen = RefCell::new(sprachpaket.code.clone());
+            aktive_sprache.beschrifte(move |sprache| {
+                if *geladen.borrow() == sprache.code {
+                    return;
+                }
+                geladen.replace(sprache.code.clone());
+
+                let schlagwoerter = schlagwoerter::lade(&sprache.code, debug);
+                for (symbole, _) in emojies_daten.borrow_mut().values_mut() {
+                    schlagwoerter::wende_an(symbole, &schlagwoerter);
+                }
+                such_index.replace(suchlogik::erstelle_index(&emojies_daten.borrow()));
+                suchfeld.emit_by_name::<()>("changed", &[]);
+            });
+        }
+
+        // settings.ini und .list-Dateien, die von außen geändert werden, gleich übernehmen
+        let dateiwaechter = {
+            let einstellungen = Rc::clone(&einstellungen);
+            let emojies_daten = Rc::clone(&emojies_daten);
+            let such_index = Rc::clone(&such_index);
+            let clipboard = Rc::clone(&clipboard);
+            let window = Rc::clone(&window);
+            let suchfeld = suchfeld.clone();
+            let aktive_sprache = Rc::clone(&aktive_sprache);
+            dateiwaechter::beobachte(pfade::konfig_ordner(), debug, move |aenderungen| {
+                let mut grids_neu = false;
+
+                if aenderungen.contains(&Aenderung::Einstellungen) {
+                    let alte_groesse = einstellungen.emoji_size.get();
+                    let alte_sprache = einstellungen.sprache.borrow().clone();
+                    einstellungen.uebernimm(&settings::lade_settings());
+
+                    let neue_groesse = einstellungen.emoji_size.get();
+                    if neue_groesse != alte_groesse {
+                        emoji_tabs::aktualisiere_emoji_style(neue_groesse);
+                        emoji_tabs::aktualisiere_tablabel_style(neue_groesse);
+                        grids_neu = true;       // Buttongröße wird beim Anlegen gesetzt
+                    }
+
+                    let neue_sprache = einstellungen.sprache.borrow().clone();
+                    if neue_sprache != alte_sprache {
+                        aktive_sprache.wechsle(&neue_sprache, debug);
+                    }
+
+                    let probleme = einstellungen.probleme.borrow().join("\n");
+                    if !probleme.is_empty() {
+                        settings::zeige_warnung(&window, &aktive_sprache.aktuell().settings_problems, &probleme);
+                    }
+                }
+
+                for aenderung in aenderungen {
+                    if let Aenderung::Liste(datei) = aenderung {
+                        grids_neu |= emoji_tabs::lade_kategorie_neu(&mut emojies_daten.borrow_mut(), datei);
+                    }
+                }
+
+                if grids_neu {
+                    emoji_tabs::baue_grids_neu(
+                        Rc::clone(&emojies_daten),
+                        Rc::clone(&clipboard),
+                        Rc::clone(&window),
+                        Rc::clone(&einstellungen),
+                    );
+                    such_index.replace(suchlogik::erstelle_index(&emojies_daten.borrow()));
+                }
+
+                // Laufende Suche mit neuen Begriffen bzw. geänderter Einstellung wiederholen
+                suchfeld.emit_by_name::<()>("changed", &[]);
+            })
+        };
+
         // Variabeln für Suchfunktion und verhalten der Entertaste
         let emojies_daten_suchfeld = Rc::clone(&emojies_daten);
         let einstellungen_suchfeld = Rc::clone(&einstellungen);
@@ -395,7 +597,7 @@ fn main() {
         let window_controller = Rc::clone(&window);
         controller.connect_key_pressed(move |_, keyval, _, _| {
             if keyval == gdk::Key::Escape {
-                window_controller.close();
+                schliesse_fenster(&window_controller);
                 gtk::glib::Propagation::Stop
             } else {
                 gtk::glib::Propagation::Proceed
@@ -432,7 +634,8 @@ fn main() {
             shortcut::zeige_setup_dialog(
                 window.as_ref(),
                 &einstellungen,
-                Rc::clone(&sprachpaket),
+                &tastenkombi::aus_einstellung(&einstellungen.tastenkombi.borrow()),
+                aktive_sprache.aktuell(),
                 debug);
 
             if debug {
@@ -440,18 +643,42 @@ fn main() {
             }
         }
 
+        hauptfenster.replace(Some(Hauptfenster {
+            window: Rc::clone(&window),
+            suchfeld: suchfeld.clone(),
+            notebook: Rc::clone(&notebook),
+            _dateiwaechter: dateiwaechter,
+        }));
+
+        // Kategorie oder Suche aus dem ersten Aufruf vor dem Anzeigen setzen
+        if let Some(ziel) = startziel.take()
+            && let Some(neu) = hauptfenster.borrow().as_ref()
+        {
+            zeige_startziel(neu, ziel);
+        }
+
         // GTK-Fokus-Bug-Workaround: Doppelt aufrufen, damit das Fenster wirklich im Vordergrund erscheint
         window.present();
         window.present();
 
+        // Fehlerhafte settings.ini: Werte wurden ersetzt, das soll auch ohne Terminal auffallen
+        let probleme = einstellungen.probleme.borrow().join("\n");
+        if !probleme.is_empty() {
+            settings::zeige_warnung(&window, &aktive_sprache.aktuell().settings_problems, &probleme);
+        }
+
         if debug {
             println!("⏳ {} {:?}", sprachpaket.debug_main_time_create_ui, timer.elapsed());
         }
     });
 
-    let empty: Vec<String> = vec![];
-    app.run_with_args(&empty);
-    // app.run();
+    let exit_code = app.run_with_args(&args);
+
+    // --print in der primären Instanz: Abbruch mit Escape soll als Fehler zurückkommen
+    match aufruf::lokaler_exit_code() {
+        Some(code) => glib::ExitCode::from(code),
+        None => exit_code,
+    }
 }
 
 // ███████╗██╗   ██╗███╗   ██╗ ██████╗████████╗██╗ ██████╗ ███╗   ██╗
@@ -461,14 +688,127 @@ fn main() {
 // ██║     ╚██████╔╝██║ ╚████║╚██████╗   ██║   ██║╚██████╔╝██║ ╚████║
 // ╚═╝      ╚═════╝ ╚═╝  ╚═══╝ ╚═════╝   ╚═╝   ╚═╝ ╚═════╝ ╚═╝  ╚═══╝
 
-fn kopiere_von_etc_falls_fehlend(dateiname: &str, sprachpaket: Rc<Sprache>, _debug: &bool) {
-    let ziel_pfad = dirs::config_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("emoji-picker")
-        .join(dateiname);
+// Wird für jeden Programmaufruf ausgeführt: für den ersten in der primären Instanz,
+// für alle weiteren mit den Argumenten, die GApplication von der zweiten Instanz weiterleitet.
+// Der Rückgabewert ist der Exit-Code des Aufrufers.
+fn verarbeite_aufruf(
+    app: &Application,
+    cmdline: &ApplicationCommandLine,
+    hauptfenster: &Rc<RefCell<Option<Hauptfenster>>>,
+    startziel: &Rc<RefCell<Option<Startziel>>>,
+    aktive_sprache: &AktiveSprache,
+    debug: bool,
+) -> i32 {
+    let args: Vec<String> = cmdline
+        .arguments()
+        .iter()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect();
+    // Die Argumente wurden schon vom Aufrufer geprüft, hier zählen nur die Fensteroptionen
+    let aufruf = Aufruf::aus_argumenten(&args);
+    let optionen = match aufruf.befehl {
+        Ok(Befehl::Fenster(optionen)) => optionen,
+        _ => Startoptionen::default(),
+    };
 
+    if debug {
+        println!("📨 Command line (remote: {}): {:?}", cmdline.is_remote(), optionen);
+    }
 
-    let etc_pfad = PathBuf::from("/etc/emoji-picker").join(dateiname);
+    // Kategorie vorab prüfen, damit der Aufrufer eine Fehlermeldung erhält
+    let seite = match optionen.kategorie.as_deref() {
+        Some(name) => match kategorie_seite(name) {
+            Some(seite) => Some(seite),
+            None => {
+                let mut namen: Vec<&str> = KATEGORIEN.iter().filter_map(|(datei, _)| datei.strip_suffix(".list")).collect();
+                namen.insert(0, "history");
+                cmdline.printerr_literal(&format!("❌ Unknown category '{}'. Available: {}\n", name, namen.join(", ")));
+                return 2;
+            }
+        },
+        None => None,
+    };
+
+    let offenes_fenster = hauptfenster
+        .borrow()
+        .as_ref()
+        .map(|vorhanden| Rc::clone(&vorhanden.window))
+        .filter(|window| window.surface().is_some_and(|surface| surface.is_mapped()));
+
+    // Erneuter Aufruf ohne Optionen (z.B. per Tastenkombination) schließt das offene Fenster
+    if let Some(window) = offenes_fenster {
+        if cmdline.is_remote() && optionen.ist_leer() {
+            schliesse_fenster(&window);
+            return 0;
+        }
+    }
+
+    // --lang bei einem weiteren Aufruf: laufende Instanz wechselt die Sprache (nur für diese Sitzung)
+    if let Some(code) = optionen.sprache.as_deref()
+        && cmdline.is_remote()
+        && code != aktive_sprache.aktuell().code
+    {
+        aktive_sprache.wechsle(code, debug);
+    }
+
+    if seite.is_some() || optionen.suche.is_some() {
+        startziel.replace(Some(Startziel { seite, suche: optionen.suche.clone() }));
+    }
+
+    // Baut das Fenster beim ersten Aufruf, holt es sonst nach vorne – jeweils mit Startziel
+    app.activate();
+
+    if optionen.ausgabe {
+        aufruf::warte_auf_auswahl(cmdline);
+    }
+
+    0
+}
+
+// Schließt das Fenster – oder versteckt es nur, solange ein Portal-Tastenkürzel am Prozess hängt
+fn schliesse_fenster(window: &ApplicationWindow) {
+    aufruf::melde_abbruch();
+
+    if portal::aktive_sitzung().is_none() {
+        window.close();
+    } else {
+        window.set_visible(false);
+    }
+}
+
+// Notebook-Seite zu einem Kategorienamen ("food" → food.list), Seite 0 ist der 🕓-Verlauf
+fn kategorie_seite(name: &str) -> Option<u32> {
+    if name == "history" {
+        return Some(0);
+    }
+    KATEGORIEN
+        .iter()
+        .position(|(datei, _)| datei.strip_suffix(".list") == Some(name))
+        .map(|index| index as u32 + 1)
+}
+
+// Springt zur Kategorie bzw. füllt das Suchfeld; eine Kategorie ohne Suche beendet die alte Suche
+fn zeige_startziel(fenster: &Hauptfenster, ziel: Startziel) {
+    if let Some(seite) = ziel.seite {
+        fenster.notebook.set_current_page(Some(seite));
+    }
+    match ziel.suche {
+        Some(text) => {
+            fenster.suchfeld.set_text(&text);
+            fenster.suchfeld.set_position(-1);
+        }
+        None if ziel.seite.is_some() => fenster.suchfeld.set_text(""),
+        None => {}
+    }
+}
+
+fn kopiere_von_etc_falls_fehlend(dateiname: &str, sprachpaket: Rc<Sprache>, _debug: &bool) {
+    let ziel_pfad = pfade::konfig_datei(dateiname);
+
+    // Vorlage aus dem portablen Ordner, $XDG_CONFIG_DIRS oder /etc/emoji-picker
+    let Some(etc_pfad) = pfade::vorlage(dateiname) else {
+        return;
+    };
 
     let muss_kopieren = match (fs::metadata(&ziel_pfad), fs::metadata(&etc_pfad)) {
         (Err(_), Ok(_)) => true, // Lokale Datei fehlt, aber etc-Datei existiert
@@ -485,33 +825,9 @@ fn kopiere_von_etc_falls_fehlend(dateiname: &str, sprachpaket: Rc<Sprache>, _deb
         if let Err(e) = fs::copy(&etc_pfad, &ziel_pfad) {
             eprintln!("❌ {} {}: {}", sprachpaket.debug_main_list_fail_to_copy, dateiname, e);
         } else {
-            println!("📁 {} /etc/emoji-picker: {}", sprachpaket.debug_main_list_copy_from_etc, dateiname);
+            // stderr, damit `emoji-picker search` in Pipes nur Treffer ausgibt
+            eprintln!("📁 {} {}: {}", sprachpaket.debug_main_list_copy_from_etc, etc_pfad.parent().unwrap_or(&etc_pfad).display(), dateiname);
         }
     }
 
 }
-
-fn neustart(window: Rc<ApplicationWindow>, sprachpaket: Rc<Sprache>) {
-    use std::os::unix::process::CommandExt;
-    use std::process::Command;
-    use std::env;
-
-    // Vollständigen Pfad zum aktuellen Binary holen
-    if let Ok(exe_path) = env::current_exe() {
-        // Versuch direkten Neustart
-        let _ = Command::new(exe_path)
-            .args(env::args().skip(1)) // übergibt etwaige Argumente weiter
-            .exec(); // ersetzt den aktuelle Prozess
-
-        // Falls exec() fehlschlägt: Meldung anzeigen
-        let dialog = gtk::MessageDialog::builder()
-            .transient_for(&*window)
-            .modal(true)
-            .message_type(gtk::MessageType::Info)
-            .buttons(gtk::ButtonsType::Ok)
-            .text(&sprachpaket.restart_after_change)
-            .build();
-
-        dialog.run_async(|dialog, _| dialog.close());
-    }
-}
\ No newline at end of file
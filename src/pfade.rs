@@ -0,0 +1,226 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+// ╔══════════════════════════════════════════════════════════════╗
+// ║        Wo Einstellungen, Listen und Daten liegen             ║
+// ╚══════════════════════════════════════════════════════════════╝
+//
+//   Konfigurationsordner  (settings.ini, *.list, usage.log, portal-session, locale/, keywords/)
+//     1. --config-dir <DIR>
+//     2. $EMOJI_PICKER_CONFIG
+//     3. portabel  ──► <Programmordner>/config/
+//     4. $XDG_CONFIG_HOME/emoji-picker/             Standard: ~/.config/emoji-picker/
+//
+//   Datenordner  (locale/, keywords/, emoji-picker.css), wichtigster zuerst
+//     portabel  ──► <Programmordner>/data/
+//     $XDG_DATA_HOME/emoji-picker/                  (nicht im portablen Modus)
+//     [Entwicklung: assets/usr/share/emoji-picker/ im Repository]
+//     $XDG_DATA_DIRS/emoji-picker/                  Standard: /usr/local/share, /usr/share
+//     /usr/share/emoji-picker/
+//
+//   Vorlagen der .list-Dateien (neuere Vorlagen ersetzen die Liste im Konfigurationsordner)
+//     portabel  ──► <Programmordner>/data/
+//     $XDG_CONFIG_DIRS/emoji-picker/                Standard: /etc/xdg
+//     /etc/emoji-picker/
+//
+//   Portabler Modus: --portable oder eine Datei "emoji-picker.portable" neben dem Programm
+//   (z.B. auf einem USB-Stick). Relative Angaben gelten ab dem aktuellen Verzeichnis.
+
+const APP_ORDNER: &str = "emoji-picker";
+const PORTABEL_MARKE: &str = "emoji-picker.portable";
+
+struct Ordner {
+    konfig: PathBuf,
+    programm: Option<PathBuf>,      // Some: portabler Modus
+    eigen: bool,                    // weicht vom Standardordner ab
+}
+
+static ORDNER: OnceLock<Ordner> = OnceLock::new();
+
+// Einmal beim Start aufrufen, bevor irgendetwas gelesen wird
+pub fn lege_fest(konfig_ordner: Option<&str>, portabel: bool, debug: bool) {
+    let ordner = ORDNER.get_or_init(|| ermittle(konfig_ordner, portabel));
+    if debug {
+        println!("📁 Config directory: {:?}", ordner.konfig);
+        if let Some(programm) = &ordner.programm {
+            println!("📁 Portable mode: {:?}", programm);
+        }
+        if ordner.eigen {
+            println!("📁 D-Bus name: {}", mit_kennung("de.kai_thanner.emoji_picker"));
+        }
+    }
+}
+
+#[cfg(not(test))]
+fn ordner() -> &'static Ordner {
+    ORDNER.get_or_init(|| ermittle(None, false))
+}
+
+// Tests schreiben nie in den echten Konfigurationsordner
+#[cfg(test)]
+fn ordner() -> &'static Ordner {
+    ORDNER.get_or_init(|| Ordner {
+        konfig: env::temp_dir().join(format!("emoji-picker-test-{}", std::process::id())),
+        programm: None,
+        eigen: true,
+    })
+}
+
+fn ermittle(konfig_ordner: Option<&str>, portabel: bool) -> Ordner {
+    let programm = programm_ordner().filter(|ordner| portabel || ordner.join(PORTABEL_MARKE).exists());
+
+    let konfig = konfig_ordner
+        .map(str::to_string)
+        .or_else(|| umgebung("EMOJI_PICKER_CONFIG"))
+        .map(|ordner| absolut(Path::new(&ordner)))
+        .or_else(|| programm.as_ref().map(|programm| programm.join("config")))
+        .unwrap_or_else(standard_konfig);
+    let eigen = konfig != standard_konfig();
+
+    Ordner { konfig, programm, eigen }
+}
+
+fn standard_konfig() -> PathBuf {
+    umgebung("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|ordner| ordner.is_absolute())      // relative Angaben verwirft die XDG-Spezifikation
+        .or_else(dirs::config_dir)
+        .map(|ordner| ordner.join(APP_ORDNER))
+        .unwrap_or_else(|| PathBuf::from(".").join(APP_ORDNER))
+}
+
+fn programm_ordner() -> Option<PathBuf> {
+    let programm = env::current_exe().ok()?;
+    let programm = programm.canonicalize().unwrap_or(programm);
+    programm.parent().map(Path::to_path_buf)
+}
+
+// Leere Variablen gelten als nicht gesetzt
+fn umgebung(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|wert| !wert.trim().is_empty())
+}
+
+// Eine laufende Instanz und der Dateiwächter sollen nicht vom aktuellen Verzeichnis abhängen
+pub fn absolut(pfad: &Path) -> PathBuf {
+    std::path::absolute(pfad).unwrap_or_else(|_| pfad.to_path_buf())
+}
+
+// Liste aus einer Variablen wie XDG_DATA_DIRS, jeweils mit emoji-picker/ angehängt
+fn xdg_liste(name: &str, standard: &str) -> Vec<PathBuf> {
+    umgebung(name)
+        .unwrap_or_else(|| standard.to_string())
+        .split(':')
+        .filter(|ordner| Path::new(ordner).is_absolute())
+        .map(|ordner| PathBuf::from(ordner).join(APP_ORDNER))
+        .collect()
+}
+
+fn ohne_doppelte(mut ordner: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut gesehen = Vec::new();
+    ordner.retain(|pfad| {
+        let neu = !gesehen.contains(pfad);
+        gesehen.push(pfad.clone());
+        neu
+    });
+    ordner
+}
+
+pub fn konfig_ordner() -> &'static Path {
+    &ordner().konfig
+}
+
+// Datei im Konfigurationsordner, z.B. "settings.ini" oder "smileys.list"
+pub fn konfig_datei(name: &str) -> PathBuf {
+    ordner().konfig.join(name)
+}
+
+// Anwendungs-ID bzw. D-Bus-Name: mit eigenem Konfigurationsordner ein eigener Name,
+// damit weitere Aufrufe die Instanz mit demselben Ordner erreichen und nicht die Standardinstanz.
+// FNV-1a statt DefaultHasher, der Name muss über Programmversionen hinweg gleich bleiben.
+pub fn mit_kennung(name: &str) -> String {
+    let ordner = ordner();
+    if !ordner.eigen {
+        return name.to_string();
+    }
+    let hash = ordner
+        .konfig
+        .as_os_str()
+        .as_encoded_bytes()
+        .iter()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3));
+    format!("{}.k{:016x}", name, hash)
+}
+
+// Programmaufruf für Tastenkürzel: ohne eigenen Ordner reicht "emoji-picker" aus dem PATH,
+// sonst dieses Programm samt --portable bzw. --config-dir
+pub fn aufruf() -> Vec<String> {
+    let ordner = ordner();
+    if !ordner.eigen {
+        return vec![APP_ORDNER.to_string()];
+    }
+
+    let programm = env::current_exe()
+        .map(|programm| programm.display().to_string())
+        .unwrap_or_else(|_| APP_ORDNER.to_string());
+    let mut aufruf = vec![programm];
+    if let Some(programm) = &ordner.programm {
+        aufruf.push("--portable".to_string());
+        if ordner.konfig == programm.join("config") {
+            return aufruf;
+        }
+    }
+    aufruf.push("--config-dir".to_string());
+    aufruf.push(ordner.konfig.display().to_string());
+    aufruf
+}
+
+pub fn daten_ordner() -> Vec<PathBuf> {
+    let mut ordner = Vec::new();
+
+    match &self::ordner().programm {
+        Some(programm) => ordner.push(programm.join("data")),
+        None => ordner.extend(
+            umgebung("XDG_DATA_HOME")
+                .map(PathBuf::from)
+                .filter(|daten| daten.is_absolute())
+                .or_else(dirs::data_dir)
+                .map(|daten| daten.join(APP_ORDNER)),
+        ),
+    }
+
+    if cfg!(debug_assertions) {
+        ordner.push(PathBuf::from("../assets/usr/share/emoji-picker/"));    // start aus emoji-picker/src/
+        ordner.push(PathBuf::from("./assets/usr/share/emoji-picker/"));     // start aus emoji-picker/
+    }
+
+    ordner.extend(xdg_liste("XDG_DATA_DIRS", "/usr/local/share:/usr/share"));
+
+    // start nach installation, auch wenn XDG_DATA_DIRS /usr/share nicht enthält
+    ordner.push(PathBuf::from("/usr/share/emoji-picker/"));
+
+    ohne_doppelte(ordner)
+}
+
+fn vorlagen_ordner() -> Vec<PathBuf> {
+    let mut ordner = Vec::new();
+
+    if let Some(programm) = &self::ordner().programm {
+        ordner.push(programm.join("data"));
+    }
+
+    ordner.extend(xdg_liste("XDG_CONFIG_DIRS", "/etc/xdg"));
+    ordner.push(PathBuf::from("/etc/emoji-picker/"));
+
+    ohne_doppelte(ordner)
+}
+
+// Erste vorhandene Vorlage einer .list-Datei
+pub fn vorlage(dateiname: &str) -> Option<PathBuf> {
+    vorlagen_ordner()
+        .into_iter()
+        .map(|ordner| ordner.join(dateiname))
+        .find(|pfad| pfad.is_file())
+}
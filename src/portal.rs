@@ -0,0 +1,438 @@
+use dbus::arg::{PropMap, RefArg, Variant};
+use dbus::blocking::{Connection, Proxy};
+use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+use dbus::message::MatchRule;
+use dbus::Path;
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{
+        Arc, Mutex, OnceLock,
+        mpsc::{Receiver, Sender, channel},
+    },
+    time::{Duration, Instant},
+};
+
+use crate::dbus_api::Anfrage;
+use crate::umgebung::Umgebung;
+
+// ╔══════════════════════════════════════════════════════════════╗
+// ║        Ablauf: Tastenkürzel über xdg-desktop-portal          ║
+// ╚══════════════════════════════════════════════════════════════╝
+//
+//   CreateSession ──► Request/Response ──► session_handle
+//         │
+//         ▼
+//   BindShortcuts(session_handle, "toggle") ──► Request/Response
+//         │                                      (Desktop fragt ggf. nach)
+//         ▼
+//   [ Verbindung bleibt offen ] ◄── Signal Activated(session_handle, "toggle")
+//                                          │
+//                                          ▼
+//                              "toggle_window" an den GTK-Thread
+
+const PORTAL_NAME: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PFAD: &str = "/org/freedesktop/portal/desktop";
+const PORTAL_SHORTCUTS: &str = "org.freedesktop.portal.GlobalShortcuts";
+const PORTAL_REQUEST: &str = "org.freedesktop.portal.Request";
+const PORTAL_SESSION: &str = "org.freedesktop.portal.Session";
+
+pub const SHORTCUT_ID: &str = "toggle";
+
+// Sitzung des laufenden Dienstes und die dabei gewünschte Taste
+// (gilt nur, solange dessen Verbindung besteht)
+static AKTIVE_SITZUNG: Mutex<Option<(String, String)>> = Mutex::new(None);
+
+// Neue Taste für die laufende Sitzung, samt Kanal für das Ergebnis
+type NeueTaste = (String, Sender<Result<String, String>>);
+static NEU_BINDEN: Mutex<Option<Sender<NeueTaste>>> = Mutex::new(None);
+
+// Kanal zum GTK-Thread, wird von der primären Instanz beim Start hinterlegt
+static AKTIVIERUNG: OnceLock<Sender<Anfrage>> = OnceLock::new();
+
+pub fn setze_aktivierung(sender: Sender<Anfrage>) {
+    let _ = AKTIVIERUNG.set(sender);
+}
+
+pub fn aktivierung() -> Option<Sender<Anfrage>> {
+    AKTIVIERUNG.get().cloned()
+}
+
+pub fn aktive_sitzung() -> Option<String> {
+    AKTIVE_SITZUNG.lock().ok()?.as_ref().map(|(handle, _)| handle.clone())
+}
+
+// Taste (Portal-Schreibweise), mit der die laufende Sitzung angemeldet wurde
+pub fn gebundene_taste() -> Option<String> {
+    AKTIVE_SITZUNG.lock().ok()?.as_ref().map(|(_, trigger)| trigger.clone())
+}
+
+// Bittet den laufenden Dienst, die Sitzung mit einer anderen Taste neu anzulegen.
+// None, wenn kein Dienst läuft; sonst kommt das Ergebnis wie bei der ersten Einrichtung.
+pub fn binde_neu(trigger: String) -> Option<Receiver<Result<String, String>>> {
+    let (rueckmeldung_tx, rueckmeldung_rx) = channel();
+    NEU_BINDEN.lock().ok()?.as_ref()?.send((trigger, rueckmeldung_tx)).ok()?;
+    Some(rueckmeldung_rx)
+}
+
+fn setze_aktive_sitzung(sitzung: Option<(String, String)>) {
+    if let Ok(mut aktiv) = AKTIVE_SITZUNG.lock() {
+        *aktiv = sitzung;
+    }
+}
+
+// Gibt es das GlobalShortcuts-Portal auf dem Session-Bus?
+pub fn ist_verfuegbar(umgebung: &dyn Umgebung) -> bool {
+    let Ok(conn) = umgebung.sitzungsbus() else {
+        return false;
+    };
+    let proxy = Proxy::new(PORTAL_NAME, PORTAL_PFAD, Duration::from_millis(500), &conn);
+    proxy.get::<u32>(PORTAL_SHORTCUTS, "version").is_ok()
+}
+
+// Datei mit dem Handle der zuletzt eingerichteten Sitzung.
+// Existiert sie, richtet die primäre Instanz das Tastenkürzel beim Start erneut ein.
+pub fn sitzungsdatei() -> PathBuf {
+    crate::pfade::konfig_datei("portal-session")
+}
+
+pub fn gespeicherte_sitzung() -> Option<String> {
+    fs::read_to_string(sitzungsdatei())
+        .ok()
+        .map(|inhalt| inhalt.trim().to_string())
+        .filter(|inhalt| !inhalt.is_empty())
+}
+
+fn speichere_sitzung(handle: &str) {
+    let pfad = sitzungsdatei();
+    let _ = fs::create_dir_all(pfad.parent().unwrap());
+    if let Err(e) = fs::write(&pfad, format!("{}\n", handle)) {
+        eprintln!("❌ {:?}: {}", pfad, e);
+    }
+}
+
+// Richtet Sitzung + Tastenkürzel ein und verarbeitet danach dauerhaft das Signal "Activated".
+// Läuft blockierend in einem eigenen Thread auf der übergebenen Sitzungsbus-Verbindung.
+// Das Ergebnis der Einrichtung (Session-Handle oder Fehlermeldung) geht an `rueckmeldung`,
+// jede Aktivierung als "toggle_window" an `sender`. Eine neue Taste kommt über binde_neu().
+pub fn starte_portal_dienst(
+    conn: Connection,
+    sender: Option<Sender<Anfrage>>,
+    rueckmeldung: Sender<Result<String, String>>,
+    trigger: String,
+    debug: bool,
+) {
+    let handle = match richte_sitzung_ein(&conn, &trigger, debug) {
+        Ok(handle) => handle,
+        Err(e) => {
+            let _ = rueckmeldung.send(Err(e));
+            return;
+        }
+    };
+
+    setze_aktive_sitzung(Some((handle.clone(), trigger)));
+    speichere_sitzung(&handle);
+    let (neu_tx, neu_rx) = channel::<NeueTaste>();
+    if let Ok(mut neu_binden) = NEU_BINDEN.lock() {
+        *neu_binden = Some(neu_tx);
+    }
+    let _ = rueckmeldung.send(Ok(handle.clone()));
+
+    // Nach neuer Anmeldung gilt ein anderes Session-Handle
+    let aktuell = Arc::new(Mutex::new(handle));
+    let aktuell_signal = Arc::clone(&aktuell);
+
+    let rule = MatchRule::new_signal(PORTAL_SHORTCUTS, "Activated");
+    let ergebnis = conn.add_match(rule, move |(sitzung, id, _zeit, _optionen): (Path<'static>, String, u64, PropMap), _, _| {
+        if debug {
+            println!("⌨️ Portal: Activated {} ({})", id, sitzung);
+        }
+        let eigene = aktuell_signal.lock().is_ok_and(|handle| &*sitzung == handle.as_str());
+        if eigene
+            && id == SHORTCUT_ID
+            && let Some(sender) = &sender
+        {
+            let _ = sender.send(Anfrage::Umschalten);
+        }
+        true
+    });
+
+    if let Err(e) = ergebnis {
+        eprintln!("❌ Portal: {}", e);
+        return;
+    }
+
+    // Verbindung am Leben halten, sonst schließt das Portal die Sitzung
+    'dienst: loop {
+        if let Err(e) = conn.process(Duration::from_millis(200)) {
+            eprintln!("❌ Portal: {}", e);
+            break;
+        }
+
+        // Andere Taste: alte Sitzung schließen und mit der neuen Taste neu anlegen
+        while let Ok((trigger, rueckmeldung)) = neu_rx.try_recv() {
+            let alt = aktuell.lock().map(|handle| handle.clone()).unwrap_or_default();
+            if let Err(e) = schliesse_sitzung(&conn, &alt)
+                && debug
+            {
+                println!("❌ Portal: Close {}: {}", alt, e);
+            }
+
+            match richte_sitzung_ein(&conn, &trigger, debug) {
+                Ok(handle) => {
+                    if let Ok(mut aktuell) = aktuell.lock() {
+                        *aktuell = handle.clone();
+                    }
+                    setze_aktive_sitzung(Some((handle.clone(), trigger)));
+                    speichere_sitzung(&handle);
+                    let _ = rueckmeldung.send(Ok(handle));
+                }
+                Err(e) => {
+                    let _ = rueckmeldung.send(Err(e));
+                    break 'dienst;
+                }
+            }
+        }
+    }
+
+    if let Ok(mut neu_binden) = NEU_BINDEN.lock() {
+        *neu_binden = None;
+    }
+    setze_aktive_sitzung(None);
+}
+
+fn schliesse_sitzung(conn: &Connection, handle: &str) -> Result<(), dbus::Error> {
+    let proxy = Proxy::new(PORTAL_NAME, handle, Duration::from_secs(5), conn);
+    proxy.method_call::<(), _, _, _>(PORTAL_SESSION, "Close", ())
+}
+
+fn richte_sitzung_ein(conn: &Connection, trigger: &str, debug: bool) -> Result<String, String> {
+    let proxy = Proxy::new(PORTAL_NAME, PORTAL_PFAD, Duration::from_secs(5), conn);
+
+    // 1. Sitzung anlegen
+    let mut optionen = PropMap::new();
+    optionen.insert("handle_token".into(), variant("emoji_picker_session"));
+    optionen.insert("session_handle_token".into(), variant("emoji_picker"));
+
+    let antwort = rufe_mit_antwort(conn, "emoji_picker_session", Duration::from_secs(10), || {
+        proxy
+            .method_call::<(Path<'static>,), _, _, _>(PORTAL_SHORTCUTS, "CreateSession", (optionen,))
+            .map(|_| ())
+    })?;
+
+    let handle = antwort
+        .get("session_handle")
+        .and_then(|wert| wert.0.as_str())
+        .map(|handle| handle.to_string())
+        .ok_or_else(|| "CreateSession: no session_handle".to_string())?;
+
+    if debug {
+        println!("🔑 Portal session: {}", handle);
+    }
+
+    // 2. Tastenkürzel anmelden (der Desktop kann hier einen Dialog zeigen)
+    let mut details = PropMap::new();
+    details.insert("description".into(), variant("Emoji Picker"));
+    details.insert("preferred_trigger".into(), variant(trigger));
+    let shortcuts = vec![(SHORTCUT_ID.to_string(), details)];
+
+    let mut optionen = PropMap::new();
+    optionen.insert("handle_token".into(), variant("emoji_picker_bind"));
+
+    let sitzung = Path::new(handle.clone()).map_err(|e| format!("session_handle: {}", e))?;
+
+    let antwort = rufe_mit_antwort(conn, "emoji_picker_bind", Duration::from_secs(120), || {
+        proxy
+            .method_call::<(Path<'static>,), _, _, _>(
+                PORTAL_SHORTCUTS,
+                "BindShortcuts",
+                (sitzung, shortcuts, "", optionen),
+            )
+            .map(|_| ())
+    })?;
+
+    if debug {
+        println!("⌨️ Portal shortcuts bound: {:?}", antwort.get("shortcuts"));
+    }
+
+    Ok(handle)
+}
+
+// Portal-Aufrufe antworten asynchron über ein Request-Objekt. Dessen Pfad ergibt sich aus dem
+// eigenen Busnamen und dem handle_token, daher wird das Response-Signal vor dem Aufruf abonniert.
+fn rufe_mit_antwort(
+    conn: &Connection,
+    token: &str,
+    timeout: Duration,
+    aufruf: impl FnOnce() -> Result<(), dbus::Error>,
+) -> Result<PropMap, String> {
+    let absender = conn.unique_name().trim_start_matches(':').replace('.', "_");
+    let pfad = format!("{}/request/{}/{}", PORTAL_PFAD, absender, token);
+
+    let mut rule = MatchRule::new_signal(PORTAL_REQUEST, "Response");
+    rule.path = Some(Path::new(pfad.clone()).map_err(|e| e.to_string())?);
+
+    let antwort: Arc<Mutex<Option<(u32, PropMap)>>> = Arc::new(Mutex::new(None));
+    let antwort_match = Arc::clone(&antwort);
+
+    let match_token = conn
+        .add_match(rule, move |(code, ergebnis): (u32, PropMap), _, _| {
+            if let Ok(mut antwort) = antwort_match.lock() {
+                *antwort = Some((code, ergebnis));
+            }
+            true
+        })
+        .map_err(|e| e.to_string())?;
+
+    let ergebnis = aufruf().map_err(|e| e.to_string()).and_then(|_| {
+        let start = Instant::now();
+        loop {
+            if let Some((code, ergebnis)) = antwort.lock().ok().and_then(|mut antwort| antwort.take()) {
+                // 0 = Erfolg, 1 = vom Benutzer abgebrochen, 2 = sonstiger Fehler
+                return match code {
+                    0 => Ok(ergebnis),
+                    1 => Err(format!("{}: cancelled", token)),
+                    _ => Err(format!("{}: response code {}", token, code)),
+                };
+            }
+            if start.elapsed() > timeout {
+                return Err(format!("{}: no response from portal", token));
+            }
+            conn.process(Duration::from_millis(200)).map_err(|e| e.to_string())?;
+        }
+    });
+
+    let _ = conn.remove_match(match_token);
+    ergebnis
+}
+
+fn variant(wert: &str) -> Variant<Box<dyn RefArg>> {
+    Variant(Box::new(wert.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::umgebung::{testbus::Testbus, verbinde};
+    use dbus::Message;
+    use dbus::channel::Sender as _;
+    use std::sync::mpsc::channel;
+
+    const SITZUNG: &str = "/org/freedesktop/portal/desktop/session/1_2/emoji_picker";
+
+    // Nachgebildetes GlobalShortcuts-Portal: beantwortet jeden Aufruf über ein Request-Objekt
+    // und merkt sich Methoden und gewünschte Taste. Jede Sitzung bekommt eine fortlaufende Nummer.
+    fn portal(bus: &mut Testbus) -> Arc<Mutex<Vec<String>>> {
+        let aufrufe = Arc::new(Mutex::new(Vec::new()));
+        let gemerkt = Arc::clone(&aufrufe);
+        let mut sitzungen = 0;
+
+        bus.dienst(PORTAL_NAME, move |nachricht: &Message, conn| {
+            let methode = nachricht.member()?.to_string();
+            if methode == "Get" {
+                return Some(nachricht.method_return().append1(Variant(1u32)));
+            }
+            if methode == "Close" {
+                gemerkt.lock().unwrap().push(format!("Close {}", nachricht.path()?));
+                return None;
+            }
+
+            let (token, ergebnis) = match methode.as_str() {
+                "CreateSession" => {
+                    let optionen: PropMap = nachricht.read1().ok()?;
+                    let mut ergebnis = PropMap::new();
+                    sitzungen += 1;
+                    ergebnis.insert("session_handle".into(), variant(&format!("{}{}", SITZUNG, sitzungen)));
+                    (optionen.get("handle_token")?.0.as_str()?.to_string(), ergebnis)
+                }
+                "BindShortcuts" => {
+                    let (_, shortcuts, _, optionen): (Path, Vec<(String, PropMap)>, String, PropMap) = nachricht.read4().ok()?;
+                    for (id, details) in &shortcuts {
+                        let taste = details.get("preferred_trigger").and_then(|wert| wert.0.as_str()).unwrap_or_default();
+                        gemerkt.lock().unwrap().push(format!("{} {}", id, taste));
+                    }
+                    (optionen.get("handle_token")?.0.as_str()?.to_string(), PropMap::new())
+                }
+                _ => return None,
+            };
+            gemerkt.lock().unwrap().push(methode);
+
+            let absender = nachricht.sender()?.trim_start_matches(':').replace('.', "_");
+            let pfad = format!("{}/request/{}/{}", PORTAL_PFAD, absender, token);
+            let antwort = Message::new_signal(pfad.as_str(), PORTAL_REQUEST, "Response").ok()?.append2(0u32, ergebnis);
+            let _ = conn.send(antwort);
+            Some(nachricht.method_return().append1(Path::new(pfad).ok()?))
+        });
+        aufrufe
+    }
+
+    fn aktiviere(conn: &Connection, sitzung: &str, id: &str) {
+        let signal = Message::new_signal(PORTAL_PFAD, PORTAL_SHORTCUTS, "Activated")
+            .unwrap()
+            .append3(Path::new(sitzung).unwrap(), id, 0u64)
+            .append1(PropMap::new());
+        conn.send(signal).unwrap();
+    }
+
+    #[test]
+    fn sitzung_einrichten_und_aktivieren() {
+        let Some(mut bus) = Testbus::starte() else {
+            eprintln!("dbus-daemon not found, skipping");
+            return;
+        };
+        let sitzung = format!("{}1", SITZUNG);
+        let aufrufe = portal(&mut bus);
+        let umgebung = crate::umgebung::attrappe::Attrappe::neu("").mit_bus(&bus.adresse);
+        assert!(ist_verfuegbar(&umgebung));
+
+        let (anfrage_tx, anfrage_rx) = channel();
+        let (rueckmeldung_tx, rueckmeldung_rx) = channel();
+        let conn = verbinde(&bus.adresse).unwrap();
+        std::thread::spawn(move || {
+            starte_portal_dienst(conn, Some(anfrage_tx), rueckmeldung_tx, "LOGO+period".into(), false);
+        });
+
+        let handle = rueckmeldung_rx.recv_timeout(Duration::from_secs(10)).unwrap().unwrap();
+        assert_eq!(handle, sitzung);
+        assert_eq!(*aufrufe.lock().unwrap(), ["CreateSession", "toggle LOGO+period", "BindShortcuts"]);
+        assert_eq!(aktive_sitzung(), Some(sitzung.clone()));
+        assert_eq!(gebundene_taste().as_deref(), Some("LOGO+period"));
+        assert_eq!(gespeicherte_sitzung(), Some(sitzung.clone()));
+
+        // Das Signal-Abo entsteht erst nach der Rückmeldung, daher bis zur ersten Aktivierung wiederholen
+        let desktop = verbinde(&bus.adresse).unwrap();
+        let start = Instant::now();
+        loop {
+            aktiviere(&desktop, &sitzung, SHORTCUT_ID);
+            if let Ok(anfrage) = anfrage_rx.recv_timeout(Duration::from_millis(100)) {
+                assert!(matches!(anfrage, Anfrage::Umschalten));
+                break;
+            }
+            assert!(start.elapsed() < Duration::from_secs(5), "no Activated handled");
+        }
+        while anfrage_rx.recv_timeout(Duration::from_millis(300)).is_ok() {}
+
+        // Fremde Kürzel und Sitzungen lösen nichts aus
+        aktiviere(&desktop, &sitzung, "other");
+        aktiviere(&desktop, "/org/freedesktop/portal/desktop/session/1_2/other", SHORTCUT_ID);
+        aktiviere(&desktop, &sitzung, SHORTCUT_ID);
+        assert!(matches!(anfrage_rx.recv_timeout(Duration::from_secs(5)), Ok(Anfrage::Umschalten)));
+        assert!(anfrage_rx.recv_timeout(Duration::from_millis(300)).is_err());
+
+        // Andere Taste: alte Sitzung schließen, neue anlegen
+        aufrufe.lock().unwrap().clear();
+        let neu = binde_neu("LOGO+e".into()).unwrap().recv_timeout(Duration::from_secs(10)).unwrap().unwrap();
+        assert_eq!(neu, format!("{}2", SITZUNG));
+        assert_eq!(
+            *aufrufe.lock().unwrap(),
+            [format!("Close {}", sitzung), "CreateSession".into(), "toggle LOGO+e".into(), "BindShortcuts".into()]
+        );
+        assert_eq!(gebundene_taste().as_deref(), Some("LOGO+e"));
+        assert_eq!(gespeicherte_sitzung(), Some(neu.clone()));
+
+        aktiviere(&desktop, &sitzung, SHORTCUT_ID);
+        aktiviere(&desktop, &neu, SHORTCUT_ID);
+        assert!(matches!(anfrage_rx.recv_timeout(Duration::from_secs(5)), Ok(Anfrage::Umschalten)));
+        assert!(anfrage_rx.recv_timeout(Duration::from_millis(300)).is_err());
+    }
+}
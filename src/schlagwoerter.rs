@@ -0,0 +1,159 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use crate::emoji_tabs::Symbol;
+use crate::konsole::ohne_variante;
+
+// ╔══════════════════════════════════════════════════════════════╗
+// ║        Suchbegriffe in der Sprache der Oberfläche            ║
+// ╚══════════════════════════════════════════════════════════════╝
+//
+//   Sprache "pt-BR"  ──►  Kette: pt-BR, pt
+//         │
+//         ├─► ~/.config/emoji-picker/keywords/<code>.list         eigene Begriffe
+//         ├─► $XDG_DATA_DIRS/emoji-picker/keywords/<code>.list    mitgelieferte Pakete (pfade.rs)
+//         └─► /usr/share/unicode/cldr/common/annotations/<code>.xml
+//             (+ annotationsDerived/)                             CLDR, z.B. Paket unicode-cldr-core
+//         │
+//         ▼
+//   alles zusammengeführt → Symbol::lokal  (die .list-Begriffe bleiben unverändert)
+//
+//   dieselbe Kette für "en"  → Symbol::englisch  (Option "Auch englische Suchbegriffe",
+//                              entfällt, wenn die Oberfläche selbst Englisch ist)
+//
+// Format der .list-Pakete wie die Kategorien, nur ohne Zähler:
+//   😂 tränen lachen freudentränen
+//   # Kommentare und leere Zeilen werden übersprungen
+
+// Schlüssel: Emoji ohne Variantenauswahl (U+FE0F), damit "❤" und "❤️" zusammenfinden
+type Ebene = HashMap<String, Vec<String>>;
+
+#[derive(Default)]
+pub struct Schlagwoerter {
+    sprache: Ebene,
+    englisch: Ebene,
+}
+
+const CLDR_ORDNER: [&str; 2] = [
+    "/usr/share/unicode/cldr/common/annotations/",
+    "/usr/share/unicode/cldr/common/annotationsDerived/",
+];
+
+fn paket_ordner() -> Vec<PathBuf> {
+    std::iter::once(crate::pfade::konfig_datei("keywords"))
+        .chain(crate::pfade::daten_ordner().into_iter().map(|ordner| ordner.join("keywords")))
+        .collect()
+}
+
+// "pt-BR" → ["pt-BR", "pt"], "de" → ["de"]
+fn sprachkette(code: &str) -> Vec<String> {
+    let mut kette = vec![code.to_string()];
+    if let Some(grundsprache) = code.split(['-', '_']).next()
+        && grundsprache != code
+    {
+        kette.push(grundsprache.to_string());
+    }
+    kette
+}
+
+pub fn lade(code: &str, debug: bool) -> Schlagwoerter {
+    let englisch = if sprachkette(code).iter().any(|code| code == "en") {
+        Ebene::new()
+    } else {
+        lade_ebene("en", debug)
+    };
+    Schlagwoerter { sprache: lade_ebene(code, debug), englisch }
+}
+
+fn lade_ebene(code: &str, debug: bool) -> Ebene {
+    let mut schlagwoerter = Ebene::new();
+
+    for code in sprachkette(code) {
+        for ordner in paket_ordner() {
+            let pfad = ordner.join(format!("{}.list", code));
+            if let Ok(inhalt) = fs::read_to_string(&pfad) {
+                let anzahl = lies_paket(&inhalt, &mut schlagwoerter);
+                if debug {
+                    println!("🔤 Keywords: {} entries from {:?}", anzahl, pfad);
+                }
+            }
+        }
+
+        // CLDR benennt Regionen mit Unterstrich: pt_PT, zh_Hant
+        let cldr_code = code.replace('-', "_");
+        for ordner in CLDR_ORDNER {
+            let pfad = PathBuf::from(format!("{}{}.xml", ordner, cldr_code));
+            if let Ok(inhalt) = fs::read_to_string(&pfad) {
+                let anzahl = lies_cldr(&inhalt, &mut schlagwoerter);
+                if debug {
+                    println!("🔤 Keywords: {} entries from {:?}", anzahl, pfad);
+                }
+            }
+        }
+    }
+
+    if debug && schlagwoerter.is_empty() {
+        println!("🔤 No keywords for '{}', searching the list keywords only", code);
+    }
+    schlagwoerter
+}
+
+// Hängt die Begriffe der Sprache an die geladenen Symbole (ersetzt die einer vorherigen Sprache)
+pub fn wende_an(symbole: &mut [Symbol], schlagwoerter: &Schlagwoerter) {
+    for symbol in symbole {
+        let schluessel = ohne_variante(&symbol.emoji);
+        symbol.lokal = schlagwoerter.sprache.get(&schluessel).cloned().unwrap_or_default();
+        symbol.englisch = schlagwoerter.englisch.get(&schluessel).cloned().unwrap_or_default();
+    }
+}
+
+fn ergaenze(schlagwoerter: &mut Ebene, emoji: &str, text: &str) {
+    let begriffe = schlagwoerter.entry(ohne_variante(emoji)).or_default();
+    for wort in text.split_whitespace().map(str::to_lowercase) {
+        if !begriffe.contains(&wort) {
+            begriffe.push(wort);
+        }
+    }
+}
+
+fn lies_paket(inhalt: &str, schlagwoerter: &mut Ebene) -> usize {
+    let mut anzahl = 0;
+    for zeile in inhalt.lines().map(str::trim) {
+        if zeile.is_empty() || zeile.starts_with('#') {
+            continue;
+        }
+        if let Some((emoji, begriffe)) = zeile.split_once(char::is_whitespace) {
+            ergaenze(schlagwoerter, emoji, begriffe);
+            anzahl += 1;
+        }
+    }
+    anzahl
+}
+
+// <annotation cp="😂">freude | gesicht | lachen | tränen</annotation>
+// <annotation cp="😂" type="tts">Gesicht mit Freudentränen</annotation>
+fn lies_cldr(inhalt: &str, schlagwoerter: &mut Ebene) -> usize {
+    let mut anzahl = 0;
+    for zeile in inhalt.lines().map(str::trim) {
+        let Some(rest) = zeile.strip_prefix("<annotation cp=\"") else {
+            continue;
+        };
+        let Some((emoji, rest)) = rest.split_once('"') else {
+            continue;
+        };
+        let Some(text) = rest.split_once('>').and_then(|(_, rest)| rest.strip_suffix("</annotation>")) else {
+            continue;
+        };
+        ergaenze(schlagwoerter, &entschluessle(emoji), &entschluessle(text).replace('|', " "));
+        anzahl += 1;
+    }
+    anzahl
+}
+
+// XML-Entitäten, wie sie in den CLDR-Dateien vorkommen
+fn entschluessle(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
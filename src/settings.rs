@@ -1,19 +1,21 @@
 use gtk::prelude::*;
 use gtk::{
     ApplicationWindow, Box as GtkBox, Button, CheckButton, ComboBoxText,
-    Dialog, Grid, Label, Orientation, ResponseType, SpinButton,
+    Dialog, EventControllerKey, Grid, Label, Orientation, ResponseType, SpinButton,
 };
 use std::{
 	cell::{Cell, RefCell},
 	collections::HashMap,
 	fs,
-	path::PathBuf,
+	path::{Path, PathBuf},
 	rc::Rc,
 };
 
+use crate::dateiwaechter;
 use crate::shortcut;
+use crate::tastenkombi::{self, Tastenkombi};
 use crate::emoji_tabs::Symbol;
-use crate::i18n::Sprache;
+use crate::i18n::{AktiveSprache, Sprache};
 
 #[derive(Clone, Debug)]
 pub struct Einstellungen {
@@ -22,6 +24,27 @@ pub struct Einstellungen {
     pub fenster_offen_bei_drag: Cell<bool>,
     pub emoji_size: Cell<i32>,
     pub sprache: RefCell<String>,
+    pub tastenkombi: RefCell<String>,
+    pub suche_englisch: Cell<bool>,     // englische Begriffe (keywords/en.list, .list-Dateien) zusätzlich zur Sprache durchsuchen
+    pub version: Cell<i32>,             // Aufbau der Datei, siehe SETTINGS_VERSION
+    zeilen: RefCell<Vec<String>>,       // gelesene Datei, damit Kommentare und Unbekanntes erhalten bleiben
+    pub probleme: RefCell<Vec<String>>, // Warnungen beim Lesen, z.B. "line 4: emoji_size = 500 is outside 10..=100"
+}
+
+impl Einstellungen {
+    // Werte einer neu gelesenen settings.ini übernehmen – alle Rc<Einstellungen> sehen sie sofort
+    pub fn uebernimm(&self, neu: &Einstellungen) {
+        self.setup_erledigt.set(neu.setup_erledigt.get());
+        self.fenster_schliessen.set(neu.fenster_schliessen.get());
+        self.fenster_offen_bei_drag.set(neu.fenster_offen_bei_drag.get());
+        self.emoji_size.set(neu.emoji_size.get());
+        self.sprache.replace(neu.sprache.borrow().clone());
+        self.tastenkombi.replace(neu.tastenkombi.borrow().clone());
+        self.suche_englisch.set(neu.suche_englisch.get());
+        self.version.set(neu.version.get());
+        self.zeilen.replace(neu.zeilen.borrow().clone());
+        self.probleme.replace(neu.probleme.borrow().clone());
+    }
 }
 
 // ╔══════════════════════════════════════════════════════════════╗
@@ -72,9 +95,11 @@ pub fn zeige_einstellungsfenster(
 	parent: Rc<ApplicationWindow>,
 	einstellungen: Rc<Einstellungen>,
 	emojies_daten: Rc<RefCell<HashMap<String, (Vec<Symbol>, Rc<Grid>)>>>,
-	sprachpaket: Rc<Sprache>,
+	aktive_sprache: Rc<AktiveSprache>,
 	debug: bool,
 ) {
+	let sprachpaket = aktive_sprache.aktuell();
+
 	let dialog = Dialog::builder()
 		.transient_for(parent.as_ref())
 		.title(&sprachpaket.settings_window)
@@ -92,23 +117,89 @@ pub fn zeige_einstellungsfenster(
     vbox.set_margin_end(10);
     content_area.append(&vbox);
 
+	// ⌨️ Tastenkombination aufnehmen: Button anklicken, dann neue Kombination drücken (Esc bricht ab)
+	let gewaehlte_kombi = Rc::new(RefCell::new(einstellungen.tastenkombi.borrow().clone()));
+	let tastenkombi_box = GtkBox::new(Orientation::Horizontal, 8);
+	let label_tastenkombi = Label::new(Some(&sprachpaket.hotkey_label));
+	label_tastenkombi.set_halign(gtk::Align::Start);
+	label_tastenkombi.set_valign(gtk::Align::Center);
+
+	let tastenkombi_button = Button::with_label(&tastenkombi::aus_einstellung(&gewaehlte_kombi.borrow()).anzeige());
+	tastenkombi_button.set_hexpand(true);
+	{
+		let nimmt_auf = Rc::new(Cell::new(false));
+
+		let nimmt_auf_klick = Rc::clone(&nimmt_auf);
+		let sprachpaket_klick = Rc::clone(&sprachpaket);
+		tastenkombi_button.connect_clicked(move |button| {
+			nimmt_auf_klick.set(true);
+			button.set_label(&sprachpaket_klick.hotkey_capture);
+		});
+
+		let aufnahme = EventControllerKey::new();
+		let button_aufnahme = tastenkombi_button.clone();
+		let gewaehlte_kombi_aufnahme = Rc::clone(&gewaehlte_kombi);
+		aufnahme.connect_key_pressed(move |_, taste, _, zustand| {
+			if !nimmt_auf.get() {
+				return glib::Propagation::Proceed;
+			}
+
+			let modifier = zustand & gtk::accelerator_get_default_mod_mask();
+
+			// Esc ohne Modifier → Aufnahme abbrechen, bisherige Kombination bleibt
+			if taste == gtk::gdk::Key::Escape && modifier.is_empty() {
+				nimmt_auf.set(false);
+				button_aufnahme.set_label(&tastenkombi::aus_einstellung(&gewaehlte_kombi_aufnahme.borrow()).anzeige());
+				return glib::Propagation::Stop;
+			}
+
+			// Reine Modifier-Tasten (Super, Shift, ...) → auf die eigentliche Taste warten
+			if !gtk::accelerator_valid(taste, modifier) {
+				return glib::Propagation::Stop;
+			}
+
+			let name = gtk::accelerator_name(taste.to_lower(), modifier);
+			if let Some(kombi) = Tastenkombi::aus_gtk(&name).filter(|kombi| kombi.ist_gueltig()) {
+				if debug {
+					println!("⌨️ {} → {}", name, kombi.anzeige());
+				}
+				nimmt_auf.set(false);
+				button_aufnahme.set_label(&kombi.anzeige());
+				gewaehlte_kombi_aufnahme.replace(kombi.gtk());
+			}
+			glib::Propagation::Stop
+		});
+		tastenkombi_button.add_controller(aufnahme);
+	}
+
+	tastenkombi_box.append(&label_tastenkombi);
+	tastenkombi_box.append(&tastenkombi_button);
+
 	// // 🔁 Shortcut erneut aktivieren
+	let eingerichtete_kombi: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
 	let shortcut_button = Button::with_label(&sprachpaket.set_key);
 	shortcut_button.set_tooltip_text(Some(&sprachpaket.set_key_tooltip));
 	{
 		let parent_shortcut = Rc::clone(&parent);
 		let einstellungen_shortcut = Rc::clone(&einstellungen);
 		let sprachpaket_shortcut = Rc::clone(&sprachpaket);
+		let gewaehlte_kombi_shortcut = Rc::clone(&gewaehlte_kombi);
+		let eingerichtete_kombi_shortcut = Rc::clone(&eingerichtete_kombi);
 
 		shortcut_button.connect_clicked(move |_|{
+			// Mit der gerade gewählten Kombination einrichten; gespeichert wird sie erst mit OK
+			let kombi = gewaehlte_kombi_shortcut.borrow().clone();
 			shortcut::zeige_setup_dialog(
 				parent_shortcut.as_ref(),
 				&einstellungen_shortcut,
+				&tastenkombi::aus_einstellung(&kombi),
 				Rc::clone(&sprachpaket_shortcut),
 				debug);
+			eingerichtete_kombi_shortcut.replace(Some(kombi));
 		});
 	}
 	vbox.append(&shortcut_button);
+	vbox.append(&tastenkombi_box);
 
 	// 🪟 Fenster nach Auswahl schließen
 	let fenster_schliessen_checkbox = CheckButton::with_label(&sprachpaket.close_window_get);
@@ -120,9 +211,14 @@ pub fn zeige_einstellungsfenster(
 	drag_checkbox.set_active(einstellungen.fenster_offen_bei_drag.get());
 	vbox.append(&drag_checkbox);
 
+	// 🔤 Neben den Suchbegriffen der Sprache auch die englischen durchsuchen
+	let suche_englisch_checkbox = CheckButton::with_label(&sprachpaket.search_english);
+	suche_englisch_checkbox.set_active(einstellungen.suche_englisch.get());
+	vbox.append(&suche_englisch_checkbox);
+
 	// 🔠 Emoji-Größe
 	let size_label = Label::new(Some(&format!("{} (px):", &sprachpaket.emoji_size)));
-	let emoji_size_spinner = SpinButton::with_range(10.0, 100.0, 2.0);
+	let emoji_size_spinner = SpinButton::with_range(*EMOJI_SIZE_BEREICH.start() as f64, *EMOJI_SIZE_BEREICH.end() as f64, 2.0);
 	emoji_size_spinner.set_value(einstellungen.emoji_size.get() as f64);
 	vbox.append(&size_label);
 	vbox.append(&emoji_size_spinner);
@@ -177,13 +273,33 @@ pub fn zeige_einstellungsfenster(
 	    if response == ResponseType::Ok {
 	        einstellungen_neu.fenster_schliessen.set(fenster_schliessen_checkbox.is_active());
 	        einstellungen_neu.fenster_offen_bei_drag.set(drag_checkbox.is_active());
+	        einstellungen_neu.suche_englisch.set(suche_englisch_checkbox.is_active());
 	        einstellungen_neu.emoji_size.set(emoji_size_spinner.value() as i32);
 	        crate::emoji_tabs::aktualisiere_emoji_style(einstellungen_neu.emoji_size.get());
 	        crate::emoji_tabs::aktualisiere_tablabel_style(einstellungen.emoji_size.get());
 
-	        // ausgewählte Sprache festlegen
+	        // ausgewählte Sprache festlegen und das Fenster sofort neu beschriften
 			let sprach_id = sprachwahl.active_id().unwrap_or_else(|| "system".into());
-			einstellungen.sprache.replace(sprach_id.to_string());
+			if *einstellungen.sprache.borrow() != sprach_id.as_str() {
+				einstellungen.sprache.replace(sprach_id.to_string());
+				aktive_sprache.wechsle(&sprach_id, debug);
+			}
+
+			// Geänderte Tastenkombination gleich am Desktop einrichten (ersetzt die alte),
+			// außer sie wurde über den Knopf oben schon eingerichtet
+			let kombi = gewaehlte_kombi.borrow().clone();
+			let kombi_geaendert = kombi != *einstellungen.tastenkombi.borrow();
+			if kombi_geaendert {
+				einstellungen.tastenkombi.replace(kombi.clone());
+				if eingerichtete_kombi.borrow().as_ref() != Some(&kombi) {
+					shortcut::zeige_setup_dialog(
+						parent.as_ref(),
+						&einstellungen,
+						&tastenkombi::aus_einstellung(&kombi),
+						aktive_sprache.aktuell(),
+						debug);
+				}
+			}
 
 	        // Fenstergrösse anpassen
 	        let neue_groesse = einstellungen.emoji_size.get();
@@ -208,74 +324,521 @@ pub fn zeige_einstellungsfenster(
 	}
 }
 
+// Probleme beim Lesen oder Speichern der settings.ini im Fenster anzeigen (nicht blockierend)
+pub fn zeige_warnung(parent: &ApplicationWindow, text: &str, details: &str) {
+	let dialog = gtk::MessageDialog::builder()
+		.transient_for(parent)
+		.modal(true)
+		.message_type(gtk::MessageType::Warning)
+		.buttons(gtk::ButtonsType::Ok)
+		.text(text)
+		.secondary_text(details)
+		.build();
+
+	dialog.run_async(|dialog, _| dialog.close());
+}
+
+// ╔══════════════════════════════════════════════════════════════╗
+// ║             Aufbau und Prüfung der settings.ini              ║
+// ╚══════════════════════════════════════════════════════════════╝
+//
+//   [Allgemein]
+//   version = 2                  ← fehlt bis v1.2.3 (= Version 1) → Migration beim Laden
+//   emoji_size = 20              ← Typ und Bereich geprüft, sonst Warnung + Standardwert
+//   # eigener Kommentar          ← bleibt beim Speichern erhalten
+//   unbekannt = x                ← Warnung, bleibt beim Speichern erhalten
+//   [AndererAbschnitt]           ← wird nicht ausgewertet, bleibt erhalten
+//
+//   lade_settings() ──► Leser: Zeilen → Schlüssel ──► schalter() / zahl() / text() je Feld
+//         │                                                   │
+//         │                                                   └─► probleme (stderr + Dialog)
+//         ├─► zeilen merken ──► speichere_settings() ersetzt nur die Werte bekannter Schlüssel
+//         └─► Datei anlegen bzw. migrieren (nur beim Öffnen des Fensters)
+//
+//   lies_settings()  ──► derselbe Leser, ohne Meldungen und ohne zu schreiben
+//                        (--help, completions, search, shortcut, Sprache beim Start)
+
+pub const SETTINGS_VERSION: i32 = 2;
+pub const EMOJI_SIZE_BEREICH: std::ops::RangeInclusive<i32> = 10..=100;
+
+pub fn settings_pfad() -> PathBuf {
+    crate::pfade::konfig_datei("settings.ini")
+}
+
+// Schlüssel aus [Allgemein] (bzw. vor dem ersten Abschnitt) mit Zeilennummer
+struct Leser {
+    werte: HashMap<String, (usize, String)>,
+    probleme: Vec<(usize, String)>,     // Zeilennummer, Meldung
+}
+
+impl Leser {
+    fn neu(zeilen: &[String]) -> Self {
+        let mut leser = Leser { werte: HashMap::new(), probleme: Vec::new() };
+        let mut allgemein = true;
+
+        for (index, zeile) in zeilen.iter().enumerate() {
+            let nummer = index + 1;
+            let zeile = zeile.trim();
+
+            if zeile.is_empty() || zeile.starts_with('#') || zeile.starts_with(';') {
+                continue;
+            }
+            if zeile.starts_with('[') {
+                allgemein = zeile == "[Allgemein]";
+                continue;
+            }
+            if !allgemein {
+                continue;
+            }
+
+            match zeile.split_once('=') {
+                Some((name, wert)) => {
+                    let name = name.trim().to_string();
+                    if let Some((vorher, _)) = leser.werte.get(&name) {
+                        leser.probleme.push((nummer, format!("'{}' already set in line {}, using the later value", name, vorher)));
+                    }
+                    // "emoji_size = 20   # Kommentar" – kein gültiger Wert enthält '#'
+                    let wert = wert.split_once('#').map_or(wert, |(wert, _)| wert);
+                    leser.werte.insert(name, (nummer, wert.trim().to_string()));
+                }
+                None => leser.probleme.push((nummer, format!("expected 'key = value', got '{}'", zeile))),
+            }
+        }
+        leser
+    }
+
+    fn nimm(&mut self, name: &str) -> Option<(usize, String)> {
+        self.werte.remove(name)
+    }
+
+    fn schalter(&mut self, name: &str, standard: bool) -> bool {
+        match self.nimm(name) {
+            Some((_, wert)) if wert == "true" => true,
+            Some((_, wert)) if wert == "false" => false,
+            Some((nummer, wert)) => {
+                self.probleme.push((nummer, format!("{} = '{}' is not true/false, using {}", name, wert, standard)));
+                standard
+            }
+            None => standard,
+        }
+    }
+
+    fn zahl(&mut self, name: &str, bereich: std::ops::RangeInclusive<i32>, standard: i32) -> i32 {
+        let Some((nummer, wert)) = self.nimm(name) else {
+            return standard;
+        };
+        match wert.parse::<i32>() {
+            Ok(zahl) if bereich.contains(&zahl) => zahl,
+            Ok(zahl) => {
+                let begrenzt = zahl.clamp(*bereich.start(), *bereich.end());
+                self.probleme.push((nummer, format!(
+                    "{} = {} is outside {}..={}, using {}",
+                    name, zahl, bereich.start(), bereich.end(), begrenzt
+                )));
+                begrenzt
+            }
+            Err(_) => {
+                self.probleme.push((nummer, format!("{} = '{}' is not a number, using {}", name, wert, standard)));
+                standard
+            }
+        }
+    }
+
+    fn text(&mut self, name: &str, standard: &str, gueltig: impl Fn(&str) -> bool) -> String {
+        match self.nimm(name) {
+            Some((_, wert)) if gueltig(&wert) => wert,
+            Some((nummer, wert)) => {
+                self.probleme.push((nummer, format!("{} = '{}' is invalid, using '{}'", name, wert, standard)));
+                standard.to_string()
+            }
+            None => standard.to_string(),
+        }
+    }
+
+    // Was übrig bleibt, kennt diese Version nicht; danach alle Meldungen nach Zeilen sortiert
+    fn probleme(mut self) -> Vec<String> {
+        for (name, (nummer, _)) in self.werte.drain() {
+            self.probleme.push((nummer, format!("unknown key '{}' (kept as is)", name)));
+        }
+        self.probleme.sort_by_key(|(nummer, _)| *nummer);
+        self.probleme
+            .into_iter()
+            .map(|(nummer, meldung)| format!("line {}: {}", nummer, meldung))
+            .collect()
+    }
+}
+
+fn ist_sprachcode(wert: &str) -> bool {
+    !wert.is_empty() && wert.chars().all(|zeichen| zeichen.is_ascii_alphanumeric() || zeichen == '-' || zeichen == '_')
+}
+
+fn ist_tastenkombi(wert: &str) -> bool {
+    Tastenkombi::aus_gtk(wert).is_some_and(|kombi| kombi.ist_gueltig())
+}
+
+// Einstellungen aus den Zeilen einer settings.ini, dazu die Version der Datei
+fn aus_zeilen(zeilen: Vec<String>) -> (Einstellungen, i32) {
+    let mut leser = Leser::neu(&zeilen);
+    // Bis v1.2.3 gab es keinen Versionseintrag
+    let version = leser.zahl("version", 1..=i32::MAX, 1);
+
+    let einstellungen = Einstellungen {
+        setup_erledigt: Cell::new(leser.schalter("setup_erledigt", false)),
+        fenster_schliessen: Cell::new(leser.schalter("fenster_schliessen", true)),
+        fenster_offen_bei_drag: Cell::new(leser.schalter("fenster_offen_bei_drag", true)),
+        emoji_size: Cell::new(leser.zahl("emoji_size", EMOJI_SIZE_BEREICH, 20)),
+        sprache: RefCell::new(leser.text("sprache", "system", ist_sprachcode)),
+        tastenkombi: RefCell::new(leser.text("tastenkombi", tastenkombi::STANDARD, ist_tastenkombi)),
+        suche_englisch: Cell::new(leser.schalter("suche_englisch", true)),
+        version: Cell::new(version.max(SETTINGS_VERSION)),
+        zeilen: RefCell::new(zeilen),
+        probleme: RefCell::new(Vec::new()),
+    };
+    einstellungen.probleme.replace(leser.probleme());
+    (einstellungen, version)
+}
+
+fn lies_zeilen(pfad: &Path) -> (Vec<String>, Option<String>) {
+    match fs::read_to_string(pfad) {
+        Ok(inhalt) => (inhalt.lines().map(String::from).collect(), None),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (Vec::new(), None),
+        Err(e) => (Vec::new(), Some(format!("{}, using defaults", e))),
+    }
+}
+
+// Nur lesen: fehlende Datei ergibt die Standardwerte, nichts wird angelegt, migriert oder gemeldet
+pub fn lies_settings() -> Einstellungen {
+    lies_settings_aus(&settings_pfad())
+}
+
+fn lies_settings_aus(pfad: &Path) -> Einstellungen {
+    let (zeilen, _) = lies_zeilen(pfad);
+    aus_zeilen(zeilen).0
+}
+
+// Für das Fenster: legt settings.ini an bzw. bringt sie auf die aktuelle Version und meldet Probleme
 pub fn lade_settings() -> Einstellungen {
-    let mut pfad = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-    pfad.push("emoji-picker/settings.ini");
+    lade_settings_aus(&settings_pfad())
+}
+
+fn lade_settings_aus(pfad: &Path) -> Einstellungen {
+    let (zeilen, lesefehler) = lies_zeilen(pfad);
+    let lesbar = lesefehler.is_none();
+    let neu_angelegt = lesbar && zeilen.is_empty();
+
+    let (einstellungen, version) = aus_zeilen(zeilen);
+    if lesbar {
+        dateiwaechter::merke(pfad);
+    }
 
-    if !pfad.exists() {
-        let _ = fs::create_dir_all(pfad.parent().unwrap());
-        let _ = fs::write(&pfad, "[Allgemein]\nsetup_erledigt = false\nfenster_schliessen = true\nfenster_offen_bei_drag = true\nemoji_size = 20\nsprache = system\n");
+    let mut probleme: Vec<String> = lesefehler.into_iter().collect();
+    if version > SETTINGS_VERSION {
+        probleme.push(format!("written by a newer emoji-picker (version {}), unknown keys are kept", version));
     }
-    
-    let content = fs::read_to_string(&pfad).unwrap_or_default();
-
-    let mut setup_erledigt = false;
-    let mut fenster_schliessen = true;
-    let mut fenster_offen_bei_drag = true;
-    let mut emoji_size = 20;
-    let mut sprache = "system".to_string();
-    
-    for line in content.lines() {
-        let line = line.trim();
-
-        if line.starts_with("setup_erledigt") {
-            if let Some(value) = line.split('=').nth(1) {
-                setup_erledigt = value.trim() == "true";
+    probleme.extend(einstellungen.probleme.take());
+
+    // Dieselben Meldungen nur einmal ausgeben, auch wenn die Datei mehrfach gelesen wird
+    thread_local! {
+        static GEMELDET: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+    GEMELDET.with(|gemeldet| {
+        if *gemeldet.borrow() != probleme {
+            for problem in &probleme {
+                eprintln!("⚠️  {:?}: {}", pfad, problem);
             }
+            gemeldet.replace(probleme.clone());
         }
-        if line.starts_with("fenster_schliessen") {
-            if let Some(value) = line.split('=').nth(1) {
-                fenster_schliessen = value.trim() == "true";
+    });
+    einstellungen.probleme.replace(probleme);
+
+    // Neue Datei anlegen bzw. alte Fassung auf die aktuelle Version bringen
+    if lesbar && (neu_angelegt || version < SETTINGS_VERSION) {
+        if !neu_angelegt {
+            let sicherung = pfad.with_extension(format!("ini.v{}", version));
+            match fs::copy(pfad, &sicherung) {
+                Ok(_) => eprintln!("🔁 {:?}: version {} → {} (backup: {:?})", pfad, version, SETTINGS_VERSION, sicherung),
+                Err(e) => eprintln!("❌ {:?}: {}", sicherung, e),
             }
         }
-        if line.starts_with("fenster_offen_bei_drag") {
-            if let Some(value) = line.split('=').nth(1) {
-                fenster_offen_bei_drag = value.trim() == "true";
+        if let Err(e) = speichere_settings_nach(&einstellungen, pfad) {
+            eprintln!("❌ {:?}: {}", pfad, e);
+        }
+    }
+
+    einstellungen
+}
+
+// Übernimmt einen Kommentar hinter dem Wert in derselben Spalte wie bisher
+fn mit_kommentar(neu: String, alt: &str) -> String {
+    let gleich = alt.find('=').unwrap_or(0);
+    match alt[gleich..].find('#') {
+        Some(abstand) => {
+            let spalte = gleich + abstand;
+            if neu.len() < spalte {
+                format!("{:<spalte$}{}", neu, &alt[spalte..])
+            } else {
+                format!("{} {}", neu, &alt[spalte..])
             }
         }
-        if line.starts_with("emoji_size") {
-            if let Some(value) = line.split('=').nth(1) {
-                emoji_size = value.trim().parse().unwrap_or(30);
+        None => neu,
+    }
+}
+
+// Aktuelle Werte in Schreibreihenfolge, version zuerst
+fn werte(einstellungen: &Einstellungen) -> Vec<(&'static str, String)> {
+    vec![
+        ("version", einstellungen.version.get().to_string()),
+        ("setup_erledigt", einstellungen.setup_erledigt.get().to_string()),
+        ("fenster_schliessen", einstellungen.fenster_schliessen.get().to_string()),
+        ("fenster_offen_bei_drag", einstellungen.fenster_offen_bei_drag.get().to_string()),
+        ("emoji_size", einstellungen.emoji_size.get().to_string()),
+        ("sprache", einstellungen.sprache.borrow().clone()),
+        ("tastenkombi", einstellungen.tastenkombi.borrow().clone()),
+        ("suche_englisch", einstellungen.suche_englisch.get().to_string()),
+    ]
+}
+
+// Schreibt die Werte in die gelesenen Zeilen zurück: Kommentare, andere Abschnitte und
+// unbekannte Schlüssel bleiben stehen, fehlende Schlüssel kommen ans Ende von [Allgemein]
+pub fn speichere_settings(einstellungen: &Einstellungen) -> std::io::Result<()> {
+    speichere_settings_nach(einstellungen, &settings_pfad())
+}
+
+fn speichere_settings_nach(einstellungen: &Einstellungen, pfad: &Path) -> std::io::Result<()> {
+    let mut offen = werte(einstellungen);
+    let bekannt: Vec<&str> = offen.iter().map(|(name, _)| *name).collect();
+    let mut vorlage = einstellungen.zeilen.borrow().clone();
+
+    // Seit dem Lesen von außen geändert: nur die eigenen Änderungen auf den neuen Stand
+    // der Datei schreiben, statt ihn mit dem alten zu überschreiben
+    let platte = match fs::read_to_string(pfad) {
+        Ok(inhalt) if dateiwaechter::extern_geaendert(pfad) => Some(inhalt.lines().map(String::from).collect::<Vec<_>>()),
+        Ok(_) => None,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+    let zusammengefuehrt = platte.is_some();
+    if let Some(platte) = platte {
+        let basis = werte(&aus_zeilen(vorlage).0);
+        let dort = werte(&aus_zeilen(platte.clone()).0);
+        offen = offen
+            .into_iter()
+            .zip(basis)
+            .zip(dort)
+            .filter(|(((name, unser), (_, alt)), (_, dort))| {
+                if unser != alt && dort != alt && dort != unser {
+                    eprintln!("🔀 settings.ini: '{}' changed on disk ({}) and in the picker ({}), keeping {}", name, dort, unser, unser);
+                }
+                unser != alt
+            })
+            .map(|((wert, _), _)| wert)
+            .collect();
+        vorlage = platte;
+    }
+
+    let mut zeilen: Vec<String> = Vec::new();
+    let mut allgemein = true;
+    let mut hat_kopf = false;
+    let mut ende_allgemein = None;
+    let mut ersetzt: Vec<&str> = Vec::new();
+
+    for zeile in &vorlage {
+        let text = zeile.trim();
+        if text.starts_with('[') {
+            if allgemein {
+                ende_allgemein = Some(zeilen.len());
             }
+            allgemein = text == "[Allgemein]";
+            hat_kopf |= allgemein;
+            zeilen.push(zeile.clone());
+            continue;
         }
-        if line.starts_with("sprache") {
-            if let Some(value) = line.split('=').nth(1) {
-                sprache = value.trim().to_string();
+
+        let schluessel = text
+            .split_once('=')
+            .map(|(name, _)| name.trim())
+            .filter(|name| allgemein && !text.starts_with('#') && !text.starts_with(';') && bekannt.contains(name));
+
+        match schluessel {
+            Some(name) => {
+                // Der erste Eintrag bekommt den aktuellen Wert, doppelte dahinter fallen weg.
+                // Nicht zu schreibende Schlüssel (beim Zusammenführen) bleiben unverändert.
+                if let Some(position) = offen.iter().position(|(offen_name, _)| *offen_name == name) {
+                    let (name, wert) = offen.remove(position);
+                    zeilen.push(mit_kommentar(format!("{} = {}", name, wert), zeile));
+                    ersetzt.push(name);
+                } else if !ersetzt.contains(&name) {
+                    zeilen.push(zeile.clone());
+                }
             }
+            None => zeilen.push(zeile.clone()),
         }
     }
 
-    Einstellungen {
-        setup_erledigt: Cell::new(setup_erledigt),
-        fenster_schliessen: Cell::new(fenster_schliessen),
-        fenster_offen_bei_drag: Cell::new(fenster_offen_bei_drag),
-        emoji_size: Cell::new(emoji_size),
-        sprache: RefCell::new(sprache),
+    // version gehört direkt unter den Abschnittskopf
+    if let Some(position) = offen.iter().position(|(name, _)| *name == "version") {
+        let (name, wert) = offen.remove(position);
+        let kopf = zeilen.iter().position(|zeile| zeile.trim() == "[Allgemein]").map_or(0, |kopf| kopf + 1);
+        zeilen.insert(kopf, format!("{} = {}", name, wert));
+        ende_allgemein = ende_allgemein.map(|ende| if ende >= kopf { ende + 1 } else { ende });
+    }
+
+    if !offen.is_empty() {
+        let mut position = match ende_allgemein {
+            Some(position) if !allgemein => position,
+            _ => zeilen.len(),
+        };
+        // vor Leerzeilen am Abschnittsende einfügen
+        while position > 0 && zeilen[position - 1].trim().is_empty() {
+            position -= 1;
+        }
+        let neue: Vec<String> = offen.iter().map(|(name, wert)| format!("{} = {}", name, wert)).collect();
+        zeilen.splice(position..position, neue);
+    }
+    if !hat_kopf {
+        zeilen.insert(0, "[Allgemein]".to_string());
     }
+
+    fs::create_dir_all(pfad.parent().unwrap_or(pfad))?;
+    fs::write(pfad, zeilen.join("\n") + "\n")?;
+
+    dateiwaechter::merke(pfad);
+
+    // Für das nächste Speichern gilt der neue Stand, nach dem Zusammenführen auch mit den fremden Werten
+    if zusammengefuehrt {
+        einstellungen.uebernimm(&aus_zeilen(zeilen).0);
+    } else {
+        einstellungen.zeilen.replace(zeilen);
+    }
+    Ok(())
 }
 
-pub fn speichere_settings(einstellungen: &Einstellungen) {
-    let mut pfad = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-    pfad.push("emoji-picker/settings.ini");
-
-    let inhalt = format!(
-        "[Allgemein]\nsetup_erledigt = {}\nfenster_schliessen = {}\nfenster_offen_bei_drag = {}\nemoji_size = {}\nsprache = {}\n",
-        einstellungen.setup_erledigt.get(),
-        einstellungen.fenster_schliessen.get(),
-        einstellungen.fenster_offen_bei_drag.get(),
-        einstellungen.emoji_size.get(),
-        einstellungen.sprache.borrow(),
-    );
-
-    let _ = fs::write(&pfad, inhalt);
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Eigener Ordner je Test, damit parallel laufende Tests sich nicht in die Quere kommen
+    fn testdatei(name: &str, inhalt: Option<&str>) -> PathBuf {
+        let ordner = std::env::temp_dir().join(format!("emoji-picker-settings-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&ordner);
+        fs::create_dir_all(&ordner).unwrap();
+        let pfad = ordner.join("settings.ini");
+        if let Some(inhalt) = inhalt {
+            fs::write(&pfad, inhalt).unwrap();
+        }
+        pfad
+    }
+
+    fn dateien_im_ordner(pfad: &Path) -> Vec<String> {
+        let mut namen: Vec<String> = fs::read_dir(pfad.parent().unwrap())
+            .unwrap()
+            .flatten()
+            .map(|eintrag| eintrag.file_name().to_string_lossy().into_owned())
+            .collect();
+        namen.sort();
+        namen
+    }
+
+    #[test]
+    fn version_1_wird_migriert_mit_sicherung() {
+        let alt = "[Allgemein]\nemoji_size = 30\n# eigener Kommentar\nsprache = de\n";
+        let pfad = testdatei("migration", Some(alt));
+
+        let einstellungen = lade_settings_aus(&pfad);
+
+        assert_eq!(einstellungen.emoji_size.get(), 30);
+        assert_eq!(einstellungen.version.get(), SETTINGS_VERSION);
+        assert_eq!(fs::read_to_string(pfad.with_extension("ini.v1")).unwrap(), alt);
+        let neu = fs::read_to_string(&pfad).unwrap();
+        assert!(neu.starts_with(&format!("[Allgemein]\nversion = {}\nemoji_size = 30\n# eigener Kommentar\nsprache = de\n", SETTINGS_VERSION)));
+        assert!(neu.contains("suche_englisch = true"));
+
+        // Einmal migriert, bleibt es dabei
+        lade_settings_aus(&pfad);
+        assert_eq!(dateien_im_ordner(&pfad), ["settings.ini", "settings.ini.v1"]);
+    }
+
+    #[test]
+    fn kommentare_und_unbekanntes_bleiben_beim_speichern() {
+        let alt = "\
+# meine Einstellungen
+[Allgemein]
+version = 2
+emoji_size = 20          # Pixel
+unbekannt = x
+; alter Kommentar
+sprache = de
+
+[Anderes]
+emoji_size = 99
+";
+        let pfad = testdatei("speichern", Some(alt));
+
+        let einstellungen = lade_settings_aus(&pfad);
+        assert_eq!(fs::read_to_string(&pfad).unwrap(), alt);
+        assert!(einstellungen.probleme.borrow().iter().any(|problem| problem.contains("unknown key 'unbekannt'")));
+
+        einstellungen.emoji_size.set(32);
+        speichere_settings_nach(&einstellungen, &pfad).unwrap();
+
+        let neu = fs::read_to_string(&pfad).unwrap();
+        assert!(neu.starts_with("# meine Einstellungen\n[Allgemein]\nversion = 2\nemoji_size = 32          # Pixel\nunbekannt = x\n; alter Kommentar\nsprache = de\n"));
+        assert!(neu.ends_with("\n[Anderes]\nemoji_size = 99\n"));
+        assert_eq!(lies_settings_aus(&pfad).emoji_size.get(), 32);
+    }
+
+    #[test]
+    fn speichern_fuehrt_mit_aenderung_von_aussen_zusammen() {
+        let pfad = testdatei("zusammenfuehren", Some("[Allgemein]\nversion = 2\nemoji_size = 20\nsprache = de\n"));
+        let einstellungen = lade_settings_aus(&pfad);
+
+        // Editor ändert die Sprache, der Picker danach die Größe
+        fs::write(&pfad, "[Allgemein]\nversion = 2\nemoji_size = 20\nsprache = fr\n# von Hand\n").unwrap();
+        einstellungen.emoji_size.set(40);
+        speichere_settings_nach(&einstellungen, &pfad).unwrap();
+
+        assert!(fs::read_to_string(&pfad).unwrap().starts_with("[Allgemein]\nversion = 2\nemoji_size = 40\nsprache = fr\n# von Hand\n"));
+        assert_eq!(*einstellungen.sprache.borrow(), "fr");
+    }
+
+    #[test]
+    fn ungueltige_werte_ergeben_standardwerte() {
+        let pfad = testdatei("ungueltig", Some("\
+[Allgemein]
+version = 2
+fenster_schliessen = vielleicht
+emoji_size = gross
+sprache = de de
+tastenkombi = xyz
+suche_englisch = false
+"));
+
+        let einstellungen = lies_settings_aus(&pfad);
+
+        assert!(einstellungen.fenster_schliessen.get());
+        assert_eq!(einstellungen.emoji_size.get(), 20);
+        assert_eq!(*einstellungen.sprache.borrow(), "system");
+        assert_eq!(*einstellungen.tastenkombi.borrow(), tastenkombi::STANDARD);
+        assert!(!einstellungen.suche_englisch.get());
+        assert_eq!(einstellungen.probleme.borrow().len(), 4);
+
+        // Außerhalb des Bereichs: auf die Grenze gesetzt
+        let pfad = testdatei("bereich", Some("emoji_size = 500\n"));
+        assert_eq!(lies_settings_aus(&pfad).emoji_size.get(), *EMOJI_SIZE_BEREICH.end());
+    }
+
+    #[test]
+    fn lies_settings_schreibt_nie() {
+        let pfad = testdatei("nur-lesen", None);
+        let einstellungen = lies_settings_aus(&pfad);
+        assert_eq!(einstellungen.emoji_size.get(), 20);
+        assert!(dateien_im_ordner(&pfad).is_empty());
+
+        let alt = "emoji_size = 30\nemoji_size = 40\nunbekannt = x\n";
+        fs::write(&pfad, alt).unwrap();
+        let einstellungen = lies_settings_aus(&pfad);
+        assert_eq!(einstellungen.emoji_size.get(), 40);
+        assert_eq!(fs::read_to_string(&pfad).unwrap(), alt);
+        assert_eq!(dateien_im_ordner(&pfad), ["settings.ini"]);
+    }
+}
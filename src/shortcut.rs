@@ -1,11 +1,19 @@
 use gtk::prelude::*;
-use gtk::{ ApplicationWindow, MessageDialog, ButtonsType, MessageType};
+use gtk::{ ApplicationWindow, MessageDialog, ButtonsType, MessageType, ResponseType};
+use dbus::blocking::Connection;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
-use std::process::Command;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::time::{Duration, Instant};
 
+use crate::cli::ShortcutAktion;
 use crate::settings::Einstellungen; 
 use crate::settings;
 use crate::i18n::Sprache;
+use crate::portal;
+use crate::tastenkombi::{self, Tastenkombi};
+use crate::umgebung::{Befehlsausgabe, System, Umgebung};
 
 #[derive(Debug, PartialEq)]
 pub enum Desktop {
@@ -14,6 +22,14 @@ pub enum Desktop {
 	Mate,
 	Kde,
 	Gnome,
+	Budgie,
+	Pantheon,
+	Lxqt,
+	Sway,
+	Hyprland,
+	I3,
+	River,
+	Bspwm,
 	Unbekannt,
 }
 
@@ -24,14 +40,76 @@ pub struct ShortcutErgebnis {
 	pub meldung: String,
 }
 
+// Richtet `kombi` ein. Die Kombination kommt als Parameter, damit der Einstellungsdialog sie
+// vor "OK" ausprobieren kann, ohne `einstellungen.tastenkombi` zu verändern.
 pub fn zeige_setup_dialog(
 	fenster: &ApplicationWindow,
 	einstellungen: &Rc<Einstellungen>,
+	kombi: &Tastenkombi,
 	sprachpaket: Rc<Sprache>,
 	debug: bool
 ) {
-	let shortcut_info = setup_shortcut(Rc::clone(&sprachpaket), debug);
+	let kombi = kombi.clone();
 
+	// 📁 Config-Datei aktualisieren		
+    // ⏩ Auch wenn kein Shortcut möglich ist, nicht erneut fragen
+    einstellungen.setup_erledigt.set(true);
+    if let Err(e) = settings::speichere_settings(&einstellungen) {
+        settings::zeige_warnung(fenster, &sprachpaket.settings_save_failed, &e.to_string());
+    }
+
+    if debug {
+	    println!("💾 {}", sprachpaket.debug_shortcut_set_info_window);
+	}
+
+	// Fenstermanager: erst fragen, ob die Konfigurationsdatei geändert werden darf
+	let desktop = detect_desktop(&System);
+	if let Some(pfad) = wm_konfiguration(&System, &desktop) {
+		let frage = MessageDialog::builder()
+			.transient_for(fenster)
+			.modal(true)
+			.message_type(MessageType::Question)
+			.text(&format!("{} - {}", desktop_name(&desktop), sprachpaket.set_key))
+			.secondary_text(&sprachpaket.setup_wm_question.replace("{file}", &pfad.display().to_string()))
+			.build();
+		frage.add_button(&sprachpaket.setup_wm_show, ResponseType::Reject);
+		frage.add_button(&sprachpaket.setup_wm_append, ResponseType::Accept);
+
+		let fenster = fenster.clone();
+		frage.connect_response(move |frage, antwort| {
+			frage.close();
+			if antwort == ResponseType::Accept || antwort == ResponseType::Reject {
+				let eintragen = antwort == ResponseType::Accept;
+				let shortcut_info = setup_wm(&System, Rc::clone(&sprachpaket), &kombi, &desktop, eintragen, debug);
+				zeige_ergebnis(&fenster, &shortcut_info, &sprachpaket);
+			}
+		});
+		frage.show();
+		return;
+	}
+
+	let einrichtung = richte_shortcut_ein(&System, Rc::clone(&sprachpaket), &kombi, debug);
+	zeige_einrichtung(fenster, einrichtung, sprachpaket);
+}
+
+fn zeige_einrichtung(fenster: &ApplicationWindow, einrichtung: Einrichtung, sprachpaket: Rc<Sprache>) {
+	match einrichtung {
+		Einrichtung::Fertig(shortcut_info) => zeige_ergebnis(fenster, &shortcut_info, &sprachpaket),
+		// Nicht im GTK-Thread blockieren: die Antwort des Portals per Timer abholen
+		Einrichtung::Portal(portal) => {
+			let fenster = fenster.clone();
+			glib::timeout_add_local(Duration::from_millis(200), move || match portal.ergebnis() {
+				Some(shortcut_info) => {
+					zeige_ergebnis(&fenster, &shortcut_info, &sprachpaket);
+					glib::ControlFlow::Break
+				}
+				None => glib::ControlFlow::Continue,
+			});
+		}
+	}
+}
+
+fn zeige_ergebnis(fenster: &ApplicationWindow, shortcut_info: &ShortcutErgebnis, sprachpaket: &Sprache) {
 	let dialog = MessageDialog::builder()
 		.transient_for(fenster)
 		.modal(true)
@@ -51,40 +129,50 @@ pub fn zeige_setup_dialog(
 	});
 
 	dialog.show();
+}
 
-	// 📁 Config-Datei aktualisieren		
-    // ⏩ Auch wenn kein Shortcut möglich ist, nicht erneut fragen
-    einstellungen.setup_erledigt.set(true);
-    settings::speichere_settings(&einstellungen);
-
-    if debug {
-	    println!("💾 {}", sprachpaket.debug_shortcut_set_info_window);
-	}
+// Richtet `kombi` am erkannten Desktop ein. Ein bereits vorhandener emoji-picker-Eintrag
+// mit anderer Taste wird dabei umgestellt bzw. entfernt, damit nur eine Kombination übrig bleibt.
+pub fn setup_shortcut(umgebung: &dyn Umgebung, sprachpaket: Rc<Sprache>, kombi: &Tastenkombi, debug: bool) -> ShortcutErgebnis {
+	richte_shortcut_ein(umgebung, sprachpaket, kombi, debug).warte()
 }
 
-pub fn setup_shortcut(sprachpaket: Rc<Sprache>, debug: bool) -> ShortcutErgebnis {
-	let ergebnis = match detect_desktop() {
-		Desktop::Cinnamon	=> setup_cinnamon(Rc::clone(&sprachpaket), debug),
-		Desktop::Xfce		=> setup_xfce(Rc::clone(&sprachpaket)),
-		Desktop::Mate 		=> setup_mate(Rc::clone(&sprachpaket)),
-		Desktop::Kde		=> setup_kde(Rc::clone(&sprachpaket)),
-		Desktop::Gnome		=> setup_gnome(Rc::clone(&sprachpaket)),
+// Wie setup_shortcut, wartet aber nicht auf die Antwort des Portals
+fn richte_shortcut_ein(umgebung: &dyn Umgebung, sprachpaket: Rc<Sprache>, kombi: &Tastenkombi, debug: bool) -> Einrichtung {
+	let desktop = detect_desktop(umgebung);
+	let ergebnis = match desktop {
+		Desktop::Cinnamon	=> setup_cinnamon(umgebung, Rc::clone(&sprachpaket), kombi, debug),
+		Desktop::Xfce		=> setup_xfce(umgebung, Rc::clone(&sprachpaket), kombi, debug),
+		Desktop::Mate 		=> setup_mate(umgebung, Rc::clone(&sprachpaket), kombi, debug),
+		Desktop::Kde		=> setup_kde(umgebung, Rc::clone(&sprachpaket), kombi, debug),
+		Desktop::Gnome		=> setup_gnome(umgebung, Rc::clone(&sprachpaket), kombi, "GNOME", debug),
+		Desktop::Budgie		=> setup_gnome(umgebung, Rc::clone(&sprachpaket), kombi, "Budgie", debug),
+		Desktop::Pantheon	=> setup_gnome(umgebung, Rc::clone(&sprachpaket), kombi, "Pantheon", debug),
+		Desktop::Lxqt		=> setup_lxqt(umgebung, Rc::clone(&sprachpaket), kombi, debug),
+		Desktop::Sway | Desktop::Hyprland | Desktop::I3 | Desktop::River | Desktop::Bspwm
+							=> setup_wm(umgebung, Rc::clone(&sprachpaket), kombi, &desktop, true, debug),
+		Desktop::Unbekannt if portal::ist_verfuegbar(umgebung)
+							=> return setup_portal(umgebung, Rc::clone(&sprachpaket), kombi, debug),
 		Desktop::Unbekannt	=> ShortcutErgebnis {
 			desktop: "Unbekannt".into(),
 			erfolg: false,
 			meldung: sprachpaket.set_desk_unknown.clone().into(),
 		},
 	};
-	ergebnis
+	Einrichtung::Fertig(ergebnis)
 }
 
-pub fn detect_desktop() -> Desktop {
-	use std::env;
-
-	if let Ok(session) = env::var("XDG_CURRENT_DESKTOP") {
+pub fn detect_desktop(umgebung: &dyn Umgebung) -> Desktop {
+	if let Some(session) = umgebung.variable("XDG_CURRENT_DESKTOP") {
 		let session = session.to_lowercase();
 		if session.contains("cinnamon") {
 			Desktop::Cinnamon
+		} else if session.contains("budgie") {		// "Budgie:GNOME" → vor GNOME prüfen
+			Desktop::Budgie
+		} else if session.contains("pantheon") {
+			Desktop::Pantheon
+		} else if session.contains("lxqt") {
+			Desktop::Lxqt
 		} else if session.contains("xfce") {
 			Desktop::Xfce
 		} else if session.contains("mate") {
@@ -94,22 +182,168 @@ pub fn detect_desktop() -> Desktop {
 		} else if session.contains("gnome") {
 			Desktop::Gnome
 		} else {
-			Desktop::Unbekannt
+			erkenne_fenstermanager(umgebung)
 		}
+	} else {
+		erkenne_fenstermanager(umgebung)
+	}
+}
+
+// Fenstermanager ohne eigene Tastenkürzel-Verwaltung, erkannt an ihren Sockets bzw. Sitzungsnamen.
+// sway setzt zur Kompatibilität auch I3SOCK, daher zuerst SWAYSOCK prüfen.
+fn erkenne_fenstermanager(umgebung: &dyn Umgebung) -> Desktop {
+	let sitzung = ["XDG_CURRENT_DESKTOP", "XDG_SESSION_DESKTOP", "DESKTOP_SESSION"]
+		.iter()
+		.filter_map(|name| umgebung.variable(name))
+		.collect::<Vec<_>>()
+		.join(":")
+		.to_lowercase();
+
+	if umgebung.variable("SWAYSOCK").is_some() || sitzung.contains("sway") {
+		Desktop::Sway
+	} else if umgebung.variable("HYPRLAND_INSTANCE_SIGNATURE").is_some() || sitzung.contains("hyprland") {
+		Desktop::Hyprland
+	} else if umgebung.variable("I3SOCK").is_some() || sitzung.split(':').any(|teil| teil == "i3") {
+		Desktop::I3
+	} else if sitzung.contains("river") {
+		Desktop::River
+	} else if umgebung.variable("BSPWM_SOCKET").is_some() || sitzung.contains("bspwm") {
+		Desktop::Bspwm
 	} else {
 		Desktop::Unbekannt
 	}
 }
 
-fn apply_gsettings(command: &[(&str, &[&str])], sprachpaket: Rc<Sprache>) -> bool {
+// Trockenlauf (`emoji-picker shortcut --dry-run`): ändernde Befehle, Dateien und D-Bus-Aufrufe
+// werden nur gemeldet. Lesende Abfragen (gsettings get, xfconf-query --list, …) laufen normal.
+struct Trockenlauf<'a> {
+	echt: &'a dyn Umgebung,
+}
+
+impl Umgebung for Trockenlauf<'_> {
+	fn abfrage(&self, befehl: &str, args: &[&str]) -> io::Result<Befehlsausgabe> {
+		self.echt.abfrage(befehl, args)
+	}
+
+	fn ausfuehren(&self, befehl: &str, args: &[&str]) -> io::Result<Befehlsausgabe> {
+		self.melde(&als_shell_befehl(befehl, args));
+		Ok(Befehlsausgabe { code: Some(0), stdout: String::new() })
+	}
+
+	fn variable(&self, name: &str) -> Option<String> {
+		self.echt.variable(name)
+	}
+
+	fn ordnerinhalt(&self, ordner: &Path) -> Vec<String> {
+		self.echt.ordnerinhalt(ordner)
+	}
+
+	fn existiert(&self, pfad: &Path) -> bool {
+		self.echt.existiert(pfad)
+	}
+
+	fn lies_datei(&self, pfad: &Path) -> io::Result<String> {
+		self.echt.lies_datei(pfad)
+	}
+
+	// Nur die neuen und wegfallenden Zeilen ausgeben
+	fn schreibe_datei(&self, pfad: &Path, inhalt: &str) -> io::Result<()> {
+		let alt = self.echt.lies_datei(pfad).unwrap_or_default();
+		self.melde(&format!("# {}", pfad.display()));
+		for zeile in inhalt.lines().filter(|zeile| !alt.lines().any(|alte| alte == *zeile)) {
+			self.melde(&format!("+ {}", zeile));
+		}
+		for zeile in alt.lines().filter(|zeile| !inhalt.lines().any(|neue| neue == *zeile)) {
+			self.melde(&format!("- {}", zeile));
+		}
+		Ok(())
+	}
+
+	fn kopiere_datei(&self, von: &Path, nach: &Path) -> io::Result<()> {
+		self.melde(&als_shell_befehl("cp", &[&von.to_string_lossy(), &nach.to_string_lossy()]));
+		Ok(())
+	}
+
+	fn entferne_datei(&self, pfad: &Path) -> io::Result<()> {
+		self.melde(&als_shell_befehl("rm", &[&pfad.to_string_lossy()]));
+		Ok(())
+	}
+
+	fn xdg_konfig(&self) -> PathBuf {
+		self.echt.xdg_konfig()
+	}
+
+	fn xdg_daten(&self) -> PathBuf {
+		self.echt.xdg_daten()
+	}
+
+	fn heimordner(&self) -> PathBuf {
+		self.echt.heimordner()
+	}
+
+	// Nur für Abfragen; ändernde D-Bus-Aufrufe prüfen ist_trockenlauf() und melden sich selbst
+	fn sitzungsbus(&self) -> Result<Connection, dbus::Error> {
+		self.echt.sitzungsbus()
+	}
+
+	fn programmaufruf(&self) -> Vec<String> {
+		self.echt.programmaufruf()
+	}
+
+	fn melde(&self, zeile: &str) {
+		self.echt.melde(zeile);
+	}
+
+	fn ist_trockenlauf(&self) -> bool {
+		true
+	}
+}
+
+// Befehl so ausgeben, dass er sich in eine Shell kopieren lässt
+fn als_shell_befehl(cmd: &str, args: &[&str]) -> String {
+	let mut teile = vec![cmd.to_string()];
+	for arg in args {
+		if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c)) {
+			teile.push(arg.to_string());
+		} else {
+			teile.push(format!("'{}'", arg.replace('\'', "'\\''")));
+		}
+	}
+	teile.join(" ")
+}
+
+// Befehlszeile, die das Tastenkürzel startet. Doppelte Anführungszeichen verstehen
+// g_shell_parse_argv (gsettings, xfconf), Desktop-Dateien und sh gleichermaßen.
+fn picker_befehl(umgebung: &dyn Umgebung) -> String {
+	umgebung
+		.programmaufruf()
+		.iter()
+		.map(|teil| {
+			if !teil.is_empty() && teil.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:=@+,".contains(c)) {
+				teil.clone()
+			} else {
+				let teil = teil.replace('\\', "\\\\").replace('"', "\\\"").replace('$', "\\$").replace('`', "\\`");
+				format!("\"{}\"", teil)
+			}
+		})
+		.collect::<Vec<_>>()
+		.join(" ")
+}
+
+// Text als GVariant-String für gsettings set: sonst liest gsettings ein führendes '"' als GVariant-Syntax
+fn als_gvariant_text(text: &str) -> String {
+	format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn apply_gsettings(umgebung: &dyn Umgebung, command: &[(&str, &[&str])], sprachpaket: Rc<Sprache>) -> bool {
 	let mut alles_ok = true;
 
     for (cmd, args) in command {
-        let status = Command::new(cmd).args(*args).status();
+        let status = umgebung.ausfuehren(cmd, args);
         match status {
-            Ok(s) if s.success() => continue,
+            Ok(s) if s.erfolg() => continue,
             Ok(s) => {
-            	eprintln!("‼️ {} exit code {}", cmd, s.code().unwrap_or(-1));
+            	eprintln!("‼️ {} exit code {}", cmd, s.code.unwrap_or(-1));
             	alles_ok = false;
             },
             Err(e) => {
@@ -121,68 +355,87 @@ fn apply_gsettings(command: &[(&str, &[&str])], sprachpaket: Rc<Sprache>) -> boo
     alles_ok
 }
 
-fn setup_cinnamon(sprachpaket: Rc<Sprache>, debug: bool) -> ShortcutErgebnis {
-    println!("🛠 Versuche, Tastenkombi <Super>+. zu setzen...");
+// Liest einen gsettings-Wert als Rohtext (z.B. "'emoji-picker'" oder "['custom0']")
+fn lies_gsettings(umgebung: &dyn Umgebung, schema: &str, schluessel: &str) -> Option<String> {
+	let output = umgebung
+		.abfrage("gsettings", &["get", schema, schluessel])
+		.ok()?;
+
+	if output.erfolg() {
+		Some(output.stdout.trim().to_string())
+	} else {
+		None
+	}
+}
+
+// Vergleicht einen gespeicherten Accelerator (z.B. "'<Mod4>period'") mit der gewünschten Kombination
+fn ist_gleiche_taste(raw: &str, kombi: &Tastenkombi) -> bool {
+	Tastenkombi::aus_gtk(raw.trim().trim_matches('\'')).as_ref() == Some(kombi)
+}
+
+// Setzt die Tastenkombination in eine Meldung mit Platzhalter {key} ein
+fn mit_taste(text: &str, taste: &str) -> String {
+	text.replace("{key}", taste)
+}
+
+// Zerlegt eine GVariant-Stringliste wie "['custom0', 'custom1']" oder "@as []"
+fn zerlege_gsettings_liste(raw: &str) -> Vec<String> {
+	raw.trim_start_matches("@as")
+		.trim_matches(['[', ']', '\n', ' ', '\''].as_ref())
+		.split(',')
+		.map(|s| s.trim_matches(&['\'', ' '][..]).to_string())
+		.filter(|s| !s.is_empty())
+		.collect()
+}
+
+fn setup_cinnamon(umgebung: &dyn Umgebung, sprachpaket: Rc<Sprache>, kombi: &Tastenkombi, debug: bool) -> ShortcutErgebnis {
+    println!("🛠 Versuche, Tastenkombi {} zu setzen...", kombi.gtk());
 
     // Bestehende Liste "Eigene Tastenkombinationen" abrufen
-    let output = std::process::Command::new("gsettings")
-    	.args(&["get", "org.cinnamon.desktop.keybindings", "custom-list"])
-    	.output();
-
-    let mut list = vec![];
-
-    if let Ok(output) = output {
-    	if output.status.success() {
-    		let raw = String::from_utf8_lossy(&output.stdout);
-    		list = raw
-    			.trim_matches(['[', ']', '\n', ' ', '\''].as_ref())
-    			.split(',')
-    			.map(|s| s.trim_matches(&['\'', ' '][..]).to_string())
-    			.filter(|s| !s.is_empty())
-    			.collect();
-    	}
-    }
+    let mut list = lies_gsettings(umgebung, "org.cinnamon.desktop.keybindings", "custom-list")
+    	.map(|raw| zerlege_gsettings_liste(&raw))
+    	.unwrap_or_default();
 
     // Prüfen, ob emoji-picker bereits eingetragen ist
+    let mut eigener_eintrag = None;
     for eintrag in &list {
-    	let full_path = format!("/org/cinnamon/desktop/keybindings/custom-keybindings/{}/", eintrag);
-    	let output = std::process::Command::new("gsettings")
-    		.args(&[
-    			"get",
-    			&format!("org.cinnamon.desktop.keybindings.custom-keybinding:{}", full_path),
-    			"command",
-    		])
-    		.output();
-
-    	if let Ok(output) = output {
-    		if output.status.success() {
-    			let raw = String::from_utf8_lossy(&output.stdout);
-    			if raw.contains("emoji-picker") {
-    				if debug {
-    					println!("{}: {}", sprachpaket.debug_shortcut_cinna_already_done, eintrag);
-    				}
-    				return ShortcutErgebnis {
-    					desktop: "Cinnamon".into(),
-    					erfolg: true,
-    					meldung: sprachpaket.setup_exists.clone().into(),
-    				};
+    	let full_path = format!("org.cinnamon.desktop.keybindings.custom-keybinding:/org/cinnamon/desktop/keybindings/custom-keybindings/{}/", eintrag);
+    	let befehl = lies_gsettings(umgebung, &full_path, "command").unwrap_or_default();
+
+    	if befehl.contains("emoji-picker") {
+    		// binding ist hier eine Liste: "['<Super>period']"
+    		let binding = lies_gsettings(umgebung, &full_path, "binding").unwrap_or_default();
+    		if zerlege_gsettings_liste(&binding).iter().any(|taste| ist_gleiche_taste(taste, kombi)) {
+    			if debug {
+    				println!("{}: {}", sprachpaket.debug_shortcut_cinna_already_done, eintrag);
     			}
+    			return ShortcutErgebnis {
+    				desktop: "Cinnamon".into(),
+    				erfolg: true,
+    				meldung: sprachpaket.setup_exists.clone().into(),
+    			};
     		}
+    		// Eintrag mit anderer Taste → Taste ersetzen statt doppelt anlegen
+    		eigener_eintrag.get_or_insert_with(|| eintrag.clone());
     	}
-
     }
 
-
     // Eintrag suchen (custom0, custom1, ...)
-    let mut custom_key = String::new();
-    for i in 0..50 {
-    	let key = format!("custom{}", i);
-    	if !list.contains(&key) {
-    		custom_key = key;
-    		list.push(custom_key.clone());
-    		break;
+    let custom_key = match eigener_eintrag {
+    	Some(eintrag) => eintrag,
+    	None => {
+    		let mut custom_key = String::new();
+    		for i in 0..50 {
+    			let key = format!("custom{}", i);
+    			if !list.contains(&key) {
+    				custom_key = key;
+    				list.push(custom_key.clone());
+    				break;
+    			}
+    		}
+    		custom_key
     	}
-    }
+    };
 
     // Pfad zum Ziel
     let full_path = format!("org.cinnamon.desktop.keybindings.custom-keybinding:/org/cinnamon/desktop/keybindings/custom-keybindings/{}/", custom_key);
@@ -195,10 +448,12 @@ fn setup_cinnamon(sprachpaket: Rc<Sprache>, debug: bool) -> ShortcutErgebnis {
     		.collect::<Vec<_>>()
     		.join(", ")
     );
+    let binding_string = format!("['{}']", kombi.gtk());
+    let befehl = als_gvariant_text(&picker_befehl(umgebung));
     let gsettings_custom_list	= ["set", "org.cinnamon.desktop.keybindings", "custom-list", &list_string];
     let gsettings_name 			= ["set", &full_path, "name", "Emoji Picker"];
-    let gsettings_command		= ["set", &full_path, "command", "emoji-picker"];
-    let gsettings_binding		= ["set", &full_path, "binding", "['<Super>period']"];
+    let gsettings_command		= ["set", &full_path, "command", &befehl];
+    let gsettings_binding		= ["set", &full_path, "binding", &binding_string];
 
     let cmds = vec![
         ("gsettings", &gsettings_custom_list[..]),
@@ -207,10 +462,10 @@ fn setup_cinnamon(sprachpaket: Rc<Sprache>, debug: bool) -> ShortcutErgebnis {
     	("gsettings", &gsettings_binding[..]),
     ];
 
-    let erfolg = apply_gsettings(&cmds, Rc::clone(&sprachpaket));
+    let erfolg = apply_gsettings(umgebung, &cmds, Rc::clone(&sprachpaket));
 
     let meldung = if erfolg {
-    	sprachpaket.setup_done_cinna.clone().into()
+    	mit_taste(&sprachpaket.setup_done_cinna, &kombi.anzeige())
     } else {
     	sprachpaket.setup_fail_text.clone().into()
     };
@@ -222,29 +477,54 @@ fn setup_cinnamon(sprachpaket: Rc<Sprache>, debug: bool) -> ShortcutErgebnis {
     }
 }
 
-fn setup_xfce(sprachpaket: Rc<Sprache>) -> ShortcutErgebnis {
-	println!("🛠 XFCE: Versuche, Tastenkombi <Super>+. zu setzen...");
+// XFCE legt pro Taste eine Property /commands/custom/<Taste> an. Eigene Einträge
+// mit anderer Taste werden nach dem Setzen der neuen entfernt.
+const XFCE_KANAL: &str = "xfce4-keyboard-shortcuts";
 
-	let status = Command::new("xfconf-query")
-		.args(&[
-			"--channel", "xfce4-keyboard-shortcuts",
-			"--property", "/commands/custom/<Super>period",
-			"--create",
-			"--type", "string",
-			"--set", "emoji-picker",
-		])
-		.status();
+fn setup_xfce(umgebung: &dyn Umgebung, sprachpaket: Rc<Sprache>, kombi: &Tastenkombi, debug: bool) -> ShortcutErgebnis {
+	println!("🛠 XFCE: Versuche, Tastenkombi {} zu setzen...", kombi.xfce());
 
-	match status {
-		Ok(s) if s.success() => ShortcutErgebnis {
+	let ziel = format!("/commands/custom/{}", kombi.xfce());
+
+	let eigene = xfce_eigene_properties(umgebung);
+
+	if eigene.iter().all(|property| *property == ziel) && eigene.contains(&ziel) {
+		if debug {
+			println!("{}: {}", sprachpaket.debug_shortcut_already_done, ziel);
+		}
+		return ShortcutErgebnis {
 			desktop: "XFCE".into(),
 			erfolg: true,
-			meldung: sprachpaket.setup_done_xfce_gno.clone().into(),
+			meldung: sprachpaket.setup_exists.clone(),
+		};
+	}
+
+	let status = umgebung.ausfuehren("xfconf-query", &[
+		"--channel", XFCE_KANAL,
+		"--property", &ziel,
+		"--create",
+		"--type", "string",
+		"--set", &picker_befehl(umgebung),
+	]);
+
+	match status {
+		Ok(s) if s.erfolg() => {
+			// Alte Tastenkombination(en) des Pickers entfernen
+			for property in eigene.iter().filter(|property| **property != ziel) {
+				let reset = ["--channel", XFCE_KANAL, "--property", property.as_str(), "--reset"];
+				apply_gsettings(umgebung, &[("xfconf-query", &reset[..])], Rc::clone(&sprachpaket));
+			}
+
+			ShortcutErgebnis {
+				desktop: "XFCE".into(),
+				erfolg: true,
+				meldung: mit_taste(&sprachpaket.setup_done_xfce_gno, &kombi.anzeige()),
+			}
 		},
 		Ok(s) => ShortcutErgebnis {
 			desktop: "XFCE".into(),
 			erfolg: false,
-			meldung: format!("{} – exit code: {}", sprachpaket.setup_fail_xfce_1, s.code().unwrap_or(-1)),
+			meldung: format!("{} – exit code: {}", sprachpaket.setup_fail_xfce_1, s.code.unwrap_or(-1)),
 		},
 		Err(e) => ShortcutErgebnis {
 			desktop: "XFCE".into(),
@@ -254,42 +534,1836 @@ fn setup_xfce(sprachpaket: Rc<Sprache>) -> ShortcutErgebnis {
 	}
 }
 
-fn setup_mate(sprachpaket: Rc<Sprache>) -> ShortcutErgebnis {
-    ShortcutErgebnis {
-        desktop: "MATE".into(),
-        erfolg: false,
-        meldung: sprachpaket.setup_not_available.clone().into(),
-    }
+// Alle Properties mit Wert auflisten: "/commands/custom/<Super>period   emoji-picker"
+fn xfce_eigene_properties(umgebung: &dyn Umgebung) -> Vec<String> {
+	umgebung
+		.abfrage("xfconf-query", &["--channel", XFCE_KANAL, "--list", "--verbose"])
+		.map(|output| {
+			output.stdout
+				.lines()
+				.filter_map(|zeile| zeile.split_once(char::is_whitespace))
+				.filter(|(property, wert)| property.starts_with("/commands/custom/") && wert.contains("emoji-picker"))
+				.map(|(property, _)| property.to_string())
+				.collect()
+		})
+		.unwrap_or_default()
 }
 
-fn setup_kde(sprachpaket: Rc<Sprache>) -> ShortcutErgebnis {
-    ShortcutErgebnis {
-        desktop: "KDE".into(),
-        erfolg: false,
-        meldung: sprachpaket.setup_not_available.clone().into(),
-    }
+// MATE: eigene Tastenkombination über mate-settings-daemon (org.mate.control-center.keybinding),
+// Fallback auf die run-command-Plätze des Fenstermanagers Marco
+const MATE_EINTRAG: &str = "org.mate.control-center.keybinding";
+const MATE_PFAD: &str = "/org/mate/desktop/keybindings/";
+
+fn setup_mate(umgebung: &dyn Umgebung, sprachpaket: Rc<Sprache>, kombi: &Tastenkombi, debug: bool) -> ShortcutErgebnis {
+	println!("🛠 MATE: Versuche, Tastenkombi {} zu setzen...", kombi.mate());
+
+	let taste = kombi.mate();
+
+	// Bestehende Einträge abrufen (dconf liefert "custom0/", "custom1/", ...)
+	let eintraege = mate_eintraege(umgebung);
+
+	// Prüfen, ob emoji-picker bereits eingetragen ist (Daemon oder Marco)
+	let eigener_eintrag = eintraege
+		.iter()
+		.find(|eintrag| {
+			lies_gsettings(umgebung, &format!("{}:{}{}/", MATE_EINTRAG, MATE_PFAD, eintrag), "action")
+				.is_some_and(|befehl| befehl.contains("emoji-picker"))
+		})
+		.cloned();
+
+	let marco_platz = (1..=12).find(|nr| {
+		lies_gsettings(umgebung, "org.mate.Marco.keybinding-commands", &format!("command-{}", nr))
+			.is_some_and(|wert| wert.contains("emoji-picker"))
+	});
+
+	let vorhanden = match (&eigener_eintrag, marco_platz) {
+		(Some(eintrag), _) => lies_gsettings(umgebung, &format!("{}:{}{}/", MATE_EINTRAG, MATE_PFAD, eintrag), "binding")
+			.is_some_and(|binding| ist_gleiche_taste(&binding, kombi))
+			.then(|| eintrag.clone()),
+		(None, Some(nr)) => lies_gsettings(umgebung, "org.mate.Marco.global-keybindings", &format!("run-command-{}", nr))
+			.is_some_and(|binding| ist_gleiche_taste(&binding, kombi))
+			.then(|| format!("command-{}", nr)),
+		(None, None) => None,
+	};
+
+	if let Some(eintrag) = vorhanden {
+		if debug {
+			println!("{}: {}", sprachpaket.debug_shortcut_already_done, eintrag);
+		}
+		return ShortcutErgebnis {
+			desktop: "MATE".into(),
+			erfolg: true,
+			meldung: sprachpaket.setup_exists.clone(),
+		};
+	}
+
+	let mut meldung_fehler = sprachpaket.setup_fail_text.clone();
+
+	// Bereits bei Marco eingetragen → dort nur die Taste umstellen
+	let erfolg = if let (None, Some(nr)) = (&eigener_eintrag, marco_platz) {
+		let marco_binding = ["set", "org.mate.Marco.global-keybindings", &format!("run-command-{}", nr), &taste];
+		apply_gsettings(umgebung, &[("gsettings", &marco_binding[..])], Rc::clone(&sprachpaket))
+	} else {
+		// Eigenen Eintrag wiederverwenden oder freien Eintrag suchen (custom0, custom1, ...)
+		// Alle Plätze belegt → lieber abbrechen als fremde Tastenkombi überschreiben
+		let Some(custom_key) = eigener_eintrag.or_else(|| {
+			(0..50)
+				.map(|i| format!("custom{}", i))
+				.find(|key| !eintraege.contains(key))
+		}) else {
+			return ShortcutErgebnis {
+				desktop: "MATE".into(),
+				erfolg: false,
+				meldung: sprachpaket.setup_no_free_slot.clone(),
+			};
+		};
+
+		let full_path = format!("{}:{}{}/", MATE_EINTRAG, MATE_PFAD, custom_key);
+		let gsettings_name		= ["set", &full_path, "name", "Emoji Picker"];
+		let aktion				= als_gvariant_text(&picker_befehl(umgebung));
+		let gsettings_action	= ["set", &full_path, "action", &aktion];
+		let gsettings_binding	= ["set", &full_path, "binding", &taste];
+
+		let cmds = vec![
+			("gsettings", &gsettings_name[..]),
+			("gsettings", &gsettings_action[..]),
+			("gsettings", &gsettings_binding[..]),
+		];
+
+		let mut erfolg = apply_gsettings(umgebung, &cmds, Rc::clone(&sprachpaket));
+
+		// Ohne mate-settings-daemon-Schema: ersten freien run-command-Platz in Marco belegen
+		if !erfolg {
+			let frei = (1..=12).find(|nr| {
+				lies_gsettings(umgebung, "org.mate.Marco.keybinding-commands", &format!("command-{}", nr))
+					.is_some_and(|wert| wert == "''")
+			});
+
+			if let Some(nr) = frei {
+				let befehl = format!("command-{}", nr);
+				let platz = format!("run-command-{}", nr);
+				let marco_command	= ["set", "org.mate.Marco.keybinding-commands", &befehl, &aktion];
+				let marco_binding	= ["set", "org.mate.Marco.global-keybindings", &platz, &taste];
+
+				erfolg = apply_gsettings(umgebung,
+					&[("gsettings", &marco_command[..]), ("gsettings", &marco_binding[..])],
+					Rc::clone(&sprachpaket),
+				);
+			} else {
+				// Alle Plätze belegt → nur manuelle Einrichtung möglich
+				meldung_fehler = mit_taste(&sprachpaket.setup_not_available, &kombi.anzeige());
+			}
+		}
+		erfolg
+	};
+
+	ShortcutErgebnis {
+		desktop: "MATE".into(),
+		erfolg,
+		meldung: if erfolg {
+			mit_taste(&sprachpaket.setup_done_xfce_gno, &kombi.anzeige())
+		} else {
+			meldung_fehler
+		},
+	}
 }
 
-fn setup_gnome(sprachpaket: Rc<Sprache>) -> ShortcutErgebnis {
-    println!("🛠 Versuche, Tastenkombi <Super>+. zu setzen...");
+// LXQt: Kürzel über lxqt-globalkeysd (D-Bus) anlegen, sonst in globalkeyshortcuts.conf eintragen.
+// Tasten werden dort mit X-Keysym-Namen geführt, '+' in Gruppennamen als %2B, dahinter ".<id>".
+const LXQT_DIENST: &str = "org.lxqt.global_key_shortcuts";
+const LXQT_NATIVE: &str = "org.lxqt.global_key_shortcuts.native";
 
-    let cmds = vec![
-        ("gsettings", &["set", "org.gnome.settings-daemon.plugins.media-keys", "custom-keybindings", "['/org/gnome/settings-daemon/plugins/media-keys/custom-keybindings/custom0/']"][..]),
-        ("gsettings", &["set", "org.gnome.settings-daemon.plugins.media-keys.custom-keybinding:/org/gnome/settings-daemon/plugins/media-keys/custom-keybindings/custom0/", "name", "Emoji Picker"][..]),
-        ("gsettings", &["set", "org.gnome.settings-daemon.plugins.media-keys.custom-keybinding:/org/gnome/settings-daemon/plugins/media-keys/custom-keybindings/custom0/", "command", "emoji-picker"][..]),
-        ("gsettings", &["set", "org.gnome.settings-daemon.plugins.media-keys.custom-keybinding:/org/gnome/settings-daemon/plugins/media-keys/custom-keybindings/custom0/", "binding", "<Super>period"][..]),
-    ];
+fn setup_lxqt(umgebung: &dyn Umgebung, sprachpaket: Rc<Sprache>, kombi: &Tastenkombi, debug: bool) -> ShortcutErgebnis {
+	let taste = kombi.lxqt();
+	println!("🛠 LXQt: Versuche, Tastenkombi {} zu setzen...", taste);
 
-	let erfolg = apply_gsettings(&cmds, Rc::clone(&sprachpaket));
-	let meldung = if erfolg {
-	    sprachpaket.setup_done_xfce_gno.clone().into()
+	let pfad = lxqt_konfiguration(umgebung);
+	let inhalt = umgebung.lies_datei(&pfad).unwrap_or_default();
+
+	// Gruppen mit Exec=emoji-picker → (Gruppenname, Taste, id)
+	let eigene = lxqt_eigene_gruppen(&inhalt);
+
+	if eigene.iter().any(|(_, vorhanden, _)| *vorhanden == taste) {
+		if debug {
+			println!("{}: {:?}", sprachpaket.debug_shortcut_already_done, pfad);
+		}
+		return ShortcutErgebnis {
+			desktop: "LXQt".into(),
+			erfolg: true,
+			meldung: sprachpaket.setup_exists.clone(),
+		};
+	}
+
+	// 1. Laufender Daemon übernimmt und speichert den Eintrag selbst; alte Einträge dort entfernen
+	let alte_ids: Vec<u64> = eigene.iter().filter_map(|(_, _, id)| *id).collect();
+	match lxqt_registrieren(umgebung, &taste, &alte_ids) {
+		Ok(vergeben) if vergeben == taste => {
+			return ShortcutErgebnis {
+				desktop: "LXQt".into(),
+				erfolg: true,
+				meldung: mit_taste(&sprachpaket.setup_done_xfce_gno, &kombi.anzeige()),
+			};
+		}
+		Ok(vergeben) => {
+			if debug {
+				println!("‼️ lxqt-globalkeysd: '{}' != '{}'", vergeben, taste);
+			}
+		}
+		Err(e) => {
+			if debug {
+				println!("❌ {} lxqt-globalkeysd: {}", sprachpaket.debug_shortcut_apply_error, e);
+			}
+		}
+	}
+
+	// 2. Konfiguration direkt schreiben: alte eigene Gruppen raus,
+	//    neue mit der nächsten freien Nummer nach dem letzten Punkt im Gruppennamen
+	let mut neu = inhalt.clone();
+	for (gruppe, _, _) in &eigene {
+		neu = entferne_ini_gruppe(&neu, gruppe);
+	}
+
+	let naechste_nr = inhalt
+		.lines()
+		.filter_map(|zeile| zeile.trim().strip_prefix('[')?.strip_suffix(']'))
+		.filter_map(|gruppe| gruppe.rsplit('.').next()?.parse::<u64>().ok())
+		.max()
+		.map(|nr| nr + 1)
+		.unwrap_or(0);
+
+	let gruppe = format!("{}.{}", taste.replace('+', "%2B"), naechste_nr);
+	neu = setze_ini_wert(&neu, &gruppe, "Comment", "Emoji Picker");
+	neu = setze_ini_wert(&neu, &gruppe, "Enabled", "true");
+	neu = setze_ini_wert(&neu, &gruppe, "Exec", &picker_befehl(umgebung));
+
+	let ergebnis = umgebung.schreibe_datei(&pfad, &neu);
+
+	match ergebnis {
+		Ok(()) => ShortcutErgebnis {
+			desktop: "LXQt".into(),
+			erfolg: true,
+			meldung: mit_taste(&sprachpaket.setup_done_lxqt_file, &taste),
+		},
+		Err(e) => ShortcutErgebnis {
+			desktop: "LXQt".into(),
+			erfolg: false,
+			meldung: format!("{}\n\n{:?}: {}", sprachpaket.setup_fail_text, pfad, e),
+		},
+	}
+}
+
+fn lxqt_eigene_gruppen(inhalt: &str) -> Vec<(String, String, Option<u64>)> {
+	let mut eigene = Vec::new();
+	let mut gruppe: Option<&str> = None;
+
+	for zeile in inhalt.lines().map(str::trim) {
+		if let Some(name) = zeile.strip_prefix('[').and_then(|zeile| zeile.strip_suffix(']')) {
+			gruppe = Some(name);
+		} else if let (Some(name), Some(befehl)) = (gruppe, zeile.strip_prefix("Exec="))
+			&& befehl.contains("emoji-picker")
+		{
+			let (taste, id) = name.rsplit_once('.').unwrap_or((name, ""));
+			eigene.push((name.to_string(), taste.replace("%2B", "+"), id.parse().ok()));
+		}
+	}
+	eigene
+}
+
+// Entfernt alte Aktionen und liefert die Taste zurück, die der Daemon tatsächlich vergeben hat (leer bei Konflikt)
+fn lxqt_registrieren(umgebung: &dyn Umgebung, taste: &str, alte_ids: &[u64]) -> Result<String, dbus::Error> {
+	let _ = lxqt_abmelden(umgebung, alte_ids);
+
+	if umgebung.ist_trockenlauf() {
+		umgebung.melde(&format!("# D-Bus {} addCommandAction '{}' {}", LXQT_DIENST, taste, picker_befehl(umgebung)));
+		return Ok(taste.to_string());
+	}
+
+	// lxqt-globalkeysd erwartet Programm und Argumente getrennt
+	let mut aufruf = umgebung.programmaufruf();
+	let programm = aufruf.remove(0);
+
+	let conn = umgebung.sitzungsbus()?;
+	let proxy = conn.with_proxy(LXQT_DIENST, "/native", Duration::from_secs(2));
+
+	let (vergeben, _id): (String, u64) = proxy.method_call(
+		LXQT_NATIVE,
+		"addCommandAction",
+		(taste, programm, aufruf, "Emoji Picker"),
+	)?;
+	Ok(vergeben)
+}
+
+// KDE Plasma 5/6: Kürzel für emoji-picker.desktop über kglobalaccel (D-Bus) anmelden.
+// Fallback: kglobalshortcutsrc + Service-Desktopdatei schreiben (greift nach erneuter Anmeldung).
+// setForeignShortcutKeys ersetzt die Tasten der Aktion, eine alte Kombination fällt damit weg.
+const KGLOBALACCEL: &str = "org.kde.KGlobalAccel";
+const KDE_AKTION: [&str; 4] = ["emoji-picker.desktop", "_launch", "Emoji Picker", "Emoji Picker"];
+
+fn setup_kde(umgebung: &dyn Umgebung, sprachpaket: Rc<Sprache>, kombi: &Tastenkombi, debug: bool) -> ShortcutErgebnis {
+	// Qt-Keycode (Modifier | Taste) und Text wie "Meta+."
+	let Some((qt_code, kde_text)) = kombi.kde() else {
+		return ShortcutErgebnis {
+			desktop: "KDE".into(),
+			erfolg: false,
+			meldung: mit_taste(&sprachpaket.setup_not_available, &kombi.anzeige()),
+		};
+	};
+
+	println!("🛠 KDE: Versuche, Tastenkombi {} zu setzen...", kde_text);
+
+	if kde_shortcut_aktiv(umgebung, qt_code) {
+		if debug {
+			println!("{}: kglobalaccel {}", sprachpaket.debug_shortcut_already_done, KDE_AKTION[0]);
+		}
+		return ShortcutErgebnis {
+			desktop: "KDE".into(),
+			erfolg: true,
+			meldung: sprachpaket.setup_exists.clone(),
+		};
+	}
+
+	match kglobalaccel_registrieren(umgebung, qt_code) {
+		Ok(()) if umgebung.ist_trockenlauf() || kde_shortcut_aktiv(umgebung, qt_code) => {
+			// Eigener Konfigurationsordner: kglobalaccel soll nicht das installierte "emoji-picker" starten
+			if umgebung.programmaufruf() != ["emoji-picker"]
+				&& let Err(e) = schreibe_kde_dienstdatei(umgebung, &kde_text)
+				&& debug
+			{
+				println!("❌ {:?}: {}", kde_service_datei(umgebung), e);
+			}
+			return ShortcutErgebnis {
+				desktop: "KDE".into(),
+				erfolg: true,
+				meldung: mit_taste(&sprachpaket.setup_done_kde, &kde_text),
+			};
+		}
+		Ok(()) => {
+			if debug {
+				println!("‼️ kglobalaccel: {}", sprachpaket.setup_fail);
+			}
+		}
+		Err(e) => {
+			if debug {
+				println!("❌ {} kglobalaccel: {}", sprachpaket.debug_shortcut_apply_error, e);
+			}
+		}
+	}
+
+	// kglobalaccel nicht erreichbar oder Kürzel nicht übernommen → Konfiguration direkt schreiben
+	if let Err(e) = schreibe_kde_konfiguration(umgebung, &kde_text) {
+		return ShortcutErgebnis {
+			desktop: "KDE".into(),
+			erfolg: false,
+			meldung: format!("{}\n\n{}", sprachpaket.setup_fail_text, e),
+		};
+	}
+
+	let erfolg = umgebung.ist_trockenlauf() || kde_konfiguration_eingetragen(umgebung, &kde_text);
+
+	ShortcutErgebnis {
+		desktop: "KDE".into(),
+		erfolg,
+		meldung: if erfolg {
+			mit_taste(&sprachpaket.setup_done_kde_file, &kde_text)
+		} else {
+			sprachpaket.setup_fail_text.clone()
+		},
+	}
+}
+
+fn kde_aktion() -> Vec<String> {
+	KDE_AKTION.iter().map(|teil| teil.to_string()).collect()
+}
+
+fn kglobalaccel_registrieren(umgebung: &dyn Umgebung, qt_code: i32) -> Result<(), dbus::Error> {
+	if umgebung.ist_trockenlauf() {
+		umgebung.melde(&format!("# D-Bus org.kde.kglobalaccel setForeignShortcutKeys {:?} [{:#x}]", KDE_AKTION, qt_code));
+		return Ok(());
+	}
+
+	let conn = umgebung.sitzungsbus()?;
+	let proxy = conn.with_proxy("org.kde.kglobalaccel", "/kglobalaccel", Duration::from_secs(2));
+
+	proxy.method_call::<(), _, _, _>(KGLOBALACCEL, "doRegister", (kde_aktion(),))?;
+
+	// Plasma 6 (und KF5 ≥ 5.90) erwartet QKeySequence als a(ai), ältere Versionen eine Liste von ints
+	let tasten: Vec<(Vec<i32>,)> = vec![(vec![qt_code, 0, 0, 0],)];
+	if proxy.method_call::<(), _, _, _>(KGLOBALACCEL, "setForeignShortcutKeys", (kde_aktion(), tasten)).is_err() {
+		proxy.method_call::<(), _, _, _>(KGLOBALACCEL, "setForeignShortcut", (kde_aktion(), vec![qt_code]))?;
+	}
+	Ok(())
+}
+
+// Meldet kglobalaccel für emoji-picker.desktop die gewünschte Taste?
+fn kde_shortcut_aktiv(umgebung: &dyn Umgebung, qt_code: i32) -> bool {
+	let Ok(conn) = umgebung.sitzungsbus() else {
+		return false;
+	};
+	let proxy = conn.with_proxy("org.kde.kglobalaccel", "/kglobalaccel", Duration::from_secs(2));
+
+	if let Ok((tasten,)) = proxy.method_call::<(Vec<(Vec<i32>,)>,), _, _, _>(KGLOBALACCEL, "shortcutKeys", (kde_aktion(),)) {
+		return tasten.iter().any(|(folge,)| folge.first() == Some(&qt_code));
+	}
+	if let Ok((tasten,)) = proxy.method_call::<(Vec<i32>,), _, _, _>(KGLOBALACCEL, "shortcut", (kde_aktion(),)) {
+		return tasten.contains(&qt_code);
+	}
+	false
+}
+
+fn ist_plasma6(umgebung: &dyn Umgebung) -> bool {
+	umgebung
+		.variable("KDE_SESSION_VERSION")
+		.is_some_and(|version| version.trim() == "6")
+}
+
+fn kde_service_datei(umgebung: &dyn Umgebung) -> PathBuf {
+	umgebung
+		.xdg_daten()
+		.join("kglobalaccel")
+		.join("emoji-picker.desktop")
+}
+
+fn schreibe_kde_konfiguration(umgebung: &dyn Umgebung, kde_text: &str) -> std::io::Result<()> {
+	let pfad = kde_konfiguration(umgebung);
+	let inhalt = umgebung.lies_datei(&pfad).unwrap_or_default();
+
+	// Plasma 6 führt Desktopdateien unter [services], Plasma 5 als eigene Komponente.
+	// Ein vorhandener Wert wird überschrieben, die alte Taste entfällt damit.
+	let neu = if ist_plasma6(umgebung) {
+		setze_ini_wert(&inhalt, kde_gruppe(umgebung), "_launch", kde_text)
 	} else {
-	    sprachpaket.setup_fail_text.clone().into()
+		setze_ini_wert(&inhalt, kde_gruppe(umgebung), "_launch", &format!("{},none,Emoji Picker", kde_text))
 	};
+	umgebung.schreibe_datei(&pfad, &neu)?;
 
-    ShortcutErgebnis {
-        desktop: "GNOME".into(),
-        erfolg,
-        meldung,
-    }
+	schreibe_kde_dienstdatei(umgebung, kde_text)
+}
+
+// Service-Desktopdatei, über die kglobalaccel das Kürzel beim nächsten Start lädt.
+// Sie hat Vorrang vor der installierten emoji-picker.desktop und trägt daher den eigenen Aufruf.
+fn schreibe_kde_dienstdatei(umgebung: &dyn Umgebung, kde_text: &str) -> std::io::Result<()> {
+	umgebung.schreibe_datei(
+		&kde_service_datei(umgebung),
+		&format!(
+			"[Desktop Entry]\nName=Emoji Picker\nExec={}\nIcon=emoji-picker\nType=Application\nX-KDE-Shortcuts={}\n",
+			picker_befehl(umgebung),
+			kde_text,
+		),
+	)
+}
+
+// Gegenprobe über kreadconfig, ob der Eintrag so in kglobalshortcutsrc steht, wie Plasma ihn liest
+fn kde_konfiguration_eingetragen(umgebung: &dyn Umgebung, kde_text: &str) -> bool {
+	let (programm, gruppen): (&str, &[&str]) = if ist_plasma6(umgebung) {
+		("kreadconfig6", &["--group", "services", "--group", "emoji-picker.desktop"])
+	} else {
+		("kreadconfig5", &["--group", "emoji-picker.desktop"])
+	};
+
+	let mut args = vec!["--file", "kglobalshortcutsrc"];
+	args.extend_from_slice(gruppen);
+	args.extend_from_slice(&["--key", "_launch"]);
+
+	match umgebung.abfrage(programm, &args) {
+		Ok(output) if output.erfolg() => output.stdout.trim().split(',').next() == Some(kde_text),
+		_ => false,
+	}
+}
+
+// Setzt in einer INI-Datei (KDE/Qt-Format) `schluessel=wert` in der Gruppe [gruppe].
+// Fehlt die Gruppe, wird sie angehängt; andere Einträge bleiben unverändert.
+fn setze_ini_wert(inhalt: &str, gruppe: &str, schluessel: &str, wert: &str) -> String {
+	let kopf = format!("[{}]", gruppe);
+	let zeile_neu = format!("{}={}", schluessel, wert);
+
+	let mut zeilen: Vec<String> = inhalt.lines().map(|zeile| zeile.to_string()).collect();
+
+	if let Some(start) = zeilen.iter().position(|zeile| zeile.trim() == kopf) {
+		let ende = zeilen[start + 1..]
+			.iter()
+			.position(|zeile| zeile.trim_start().starts_with('['))
+			.map(|i| start + 1 + i)
+			.unwrap_or(zeilen.len());
+
+		match zeilen[start + 1..ende].iter().position(|zeile| zeile.split('=').next().map(str::trim) == Some(schluessel)) {
+			Some(i) => zeilen[start + 1 + i] = zeile_neu,
+			None => {
+				// Vor eventuellen Leerzeilen am Gruppenende einfügen
+				let mut einfuegen = ende;
+				while einfuegen > start + 1 && zeilen[einfuegen - 1].trim().is_empty() {
+					einfuegen -= 1;
+				}
+				zeilen.insert(einfuegen, zeile_neu);
+			}
+		}
+	} else {
+		if zeilen.last().is_some_and(|zeile| !zeile.trim().is_empty()) {
+			zeilen.push(String::new());
+		}
+		zeilen.push(kopf);
+		zeilen.push(zeile_neu);
+	}
+
+	let mut ergebnis = zeilen.join("\n");
+	ergebnis.push('\n');
+	ergebnis
+}
+
+// Entfernt die Gruppe [gruppe] samt ihrer Einträge aus einer INI-Datei
+fn entferne_ini_gruppe(inhalt: &str, gruppe: &str) -> String {
+	let kopf = format!("[{}]", gruppe);
+	let mut in_gruppe = false;
+
+	let zeilen: Vec<&str> = inhalt
+		.lines()
+		.filter(|zeile| {
+			if zeile.trim_start().starts_with('[') {
+				in_gruppe = zeile.trim() == kopf;
+			}
+			!in_gruppe
+		})
+		.collect();
+
+	let mut ergebnis = zeilen.join("\n");
+	ergebnis.push('\n');
+	ergebnis
+}
+
+// GNOME und darauf aufbauende Desktops (Budgie, Pantheon) nutzen die eigenen
+// Tastenkombinationen von gnome-settings-daemon
+const GSD_SCHEMA: &str = "org.gnome.settings-daemon.plugins.media-keys";
+const GSD_EINTRAG: &str = "org.gnome.settings-daemon.plugins.media-keys.custom-keybinding";
+
+const GSD_LISTE_PFAD: &str = "/org/gnome/settings-daemon/plugins/media-keys/custom-keybindings/";
+
+// Wie bei Cinnamon: bestehende Liste lesen, eigenen Eintrag wiederverwenden oder
+// einen freien customN-Platz anhängen – fremde Tastenkombinationen bleiben erhalten.
+fn setup_gnome(umgebung: &dyn Umgebung, sprachpaket: Rc<Sprache>, kombi: &Tastenkombi, desktop: &str, debug: bool) -> ShortcutErgebnis {
+    let tastenkombi = kombi.gtk();
+    println!("🛠 Versuche, Tastenkombi {} zu setzen...", tastenkombi);
+
+    // Bestehende Liste "Eigene Tastenkombinationen" abrufen
+    let mut list = lies_gsettings(umgebung, GSD_SCHEMA, "custom-keybindings")
+    	.map(|raw| zerlege_gsettings_liste(&raw))
+    	.unwrap_or_default();
+
+    let mut eigener_eintrag = None;
+    let mut konflikte = Vec::new();
+
+    for pfad in &list {
+    	let schema = format!("{}:{}", GSD_EINTRAG, pfad);
+    	let befehl = lies_gsettings(umgebung, &schema, "command").unwrap_or_default();
+    	let binding = lies_gsettings(umgebung, &schema, "binding").unwrap_or_default();
+    	let gleiche_taste = ist_gleiche_taste(&binding, kombi);
+
+    	if befehl.contains("emoji-picker") {
+    		if gleiche_taste {
+    			if debug {
+    				println!("{}: {}", sprachpaket.debug_shortcut_already_done, pfad);
+    			}
+    			return ShortcutErgebnis {
+    				desktop: desktop.into(),
+    				erfolg: true,
+    				meldung: sprachpaket.setup_exists.clone(),
+    			};
+    		}
+    		// Eintrag mit anderer Taste → wiederverwenden statt doppelt anlegen
+    		eigener_eintrag.get_or_insert_with(|| pfad.clone());
+    	} else if gleiche_taste {
+    		let name = lies_gsettings(umgebung, &schema, "name").unwrap_or_default();
+    		konflikte.push(format!("• {} ({})", name.trim_matches('\''), befehl.trim_matches('\'')));
+    	}
+    }
+
+    if !konflikte.is_empty() {
+    	return ShortcutErgebnis {
+    		desktop: desktop.into(),
+    		erfolg: false,
+    		meldung: format!("{}\n\n{}", sprachpaket.setup_conflict, konflikte.join("\n")),
+    	};
+    }
+
+    // Freien Eintrag suchen (custom0, custom1, ...) und an die Liste anhängen
+    let pfad = match eigener_eintrag {
+    	Some(pfad) => pfad,
+    	None => {
+    		// Alle Plätze belegt → lieber abbrechen als fremde Tastenkombi überschreiben
+    		let Some(pfad) = (0..50)
+    			.map(|i| format!("{}custom{}/", GSD_LISTE_PFAD, i))
+    			.find(|pfad| !list.contains(pfad))
+    		else {
+    			return ShortcutErgebnis {
+    				desktop: desktop.into(),
+    				erfolg: false,
+    				meldung: sprachpaket.setup_no_free_slot.clone(),
+    			};
+    		};
+    		list.push(pfad.clone());
+    		pfad
+    	}
+    };
+
+    let full_path = format!("{}:{}", GSD_EINTRAG, pfad);
+    let list_string = format!(
+    	"[{}]",
+    	list.iter()
+    		.map(|s| format!("'{}'", s))
+    		.collect::<Vec<_>>()
+    		.join(", ")
+    );
+
+    let befehl = als_gvariant_text(&picker_befehl(umgebung));
+
+    let gsettings_custom_list	= ["set", GSD_SCHEMA, "custom-keybindings", &list_string];
+    let gsettings_name			= ["set", &full_path, "name", "Emoji Picker"];
+    let gsettings_command		= ["set", &full_path, "command", &befehl];
+    let gsettings_binding		= ["set", &full_path, "binding", &tastenkombi];
+
+    // Eintrag zuerst befüllen, dann erst in die Liste aufnehmen
+    let cmds = vec![
+    	("gsettings", &gsettings_name[..]),
+    	("gsettings", &gsettings_command[..]),
+    	("gsettings", &gsettings_binding[..]),
+    	("gsettings", &gsettings_custom_list[..]),
+    ];
+
+	let erfolg = apply_gsettings(umgebung, &cmds, Rc::clone(&sprachpaket));
+	let meldung = if erfolg {
+	    mit_taste(&sprachpaket.setup_done_xfce_gno, &kombi.anzeige())
+	} else {
+	    sprachpaket.setup_fail_text.clone()
+	};
+
+    ShortcutErgebnis {
+        desktop: desktop.into(),
+        erfolg,
+        meldung,
+    }
+}
+
+// ╔══════════════════════════════════════════════════════════════╗
+// ║     Fenstermanager: Zeile in der eigenen Konfiguration       ║
+// ╚══════════════════════════════════════════════════════════════╝
+//
+//   sway / i3   ~/.config/sway/config, ~/.config/i3/config   bindsym Mod4+period exec --no-startup-id emoji-picker
+//   Hyprland    ~/.config/hypr/hyprland.conf                  bind = SUPER, period, exec, emoji-picker
+//   river       ~/.config/river/init                          riverctl map normal Super period spawn emoji-picker
+//   bspwm       ~/.config/sxhkd/sxhkdrc                       super + period
+//                                                                 emoji-picker
+//
+// Der Eintrag steht zwischen zwei Markern, damit er sich später ersetzen und entfernen lässt.
+// Vor jeder Änderung wird die Datei nach <datei>.emoji-picker.bak gesichert.
+const WM_MARKER_START: &str = "# >>> emoji-picker >>>";
+const WM_MARKER_ENDE: &str = "# <<< emoji-picker <<<";
+
+fn wm_zeilen(umgebung: &dyn Umgebung, desktop: &Desktop, kombi: &Tastenkombi) -> Vec<String> {
+	let befehl = picker_befehl(umgebung);
+	match desktop {
+		Desktop::Sway | Desktop::I3 => vec![format!("bindsym {} exec --no-startup-id {}", kombi.i3(), befehl)],
+		Desktop::Hyprland => {
+			let (modifier, taste) = kombi.hyprland();
+			vec![format!("bind = {}, {}, exec, {}", modifier, taste, befehl)]
+		}
+		Desktop::River => {
+			// spawn erwartet die ganze Befehlszeile als ein Argument
+			let (modifier, taste) = kombi.river();
+			let befehl = if befehl.contains(' ') { format!("'{}'", befehl.replace('\'', "'\\''")) } else { befehl };
+			vec![format!("riverctl map normal {} {} spawn {}", modifier, taste, befehl)]
+		}
+		Desktop::Bspwm => vec![kombi.sxhkd(), format!("\t{}", befehl)],
+		_ => Vec::new(),
+	}
+}
+
+// Vorhandene Konfigurationsdatei des Fenstermanagers (None bei Desktops oder fehlender Datei)
+fn wm_konfiguration(umgebung: &dyn Umgebung, desktop: &Desktop) -> Option<PathBuf> {
+	let config = umgebung.xdg_konfig();
+	let home = umgebung.heimordner();
+
+	let kandidaten = match desktop {
+		Desktop::Sway		=> vec![config.join("sway/config"), home.join(".sway/config")],
+		Desktop::I3			=> vec![config.join("i3/config"), home.join(".i3/config")],
+		Desktop::Hyprland	=> vec![config.join("hypr/hyprland.conf")],
+		Desktop::River		=> vec![config.join("river/init")],
+		Desktop::Bspwm		=> vec![config.join("sxhkd/sxhkdrc")],
+		_					=> return None,
+	};
+	kandidaten.into_iter().find(|pfad| umgebung.existiert(pfad))
+}
+
+fn wm_schnipsel(umgebung: &dyn Umgebung, desktop: &Desktop, kombi: &Tastenkombi) -> String {
+	format!("{}\n{}\n{}", WM_MARKER_START, wm_zeilen(umgebung, desktop, kombi).join("\n"), WM_MARKER_ENDE)
+}
+
+// Zeilen zwischen den Markern
+fn wm_block_zeilen(inhalt: &str) -> Vec<String> {
+	inhalt
+		.lines()
+		.skip_while(|zeile| zeile.trim() != WM_MARKER_START)
+		.skip(1)
+		.take_while(|zeile| zeile.trim() != WM_MARKER_ENDE)
+		.map(|zeile| zeile.to_string())
+		.collect()
+}
+
+// Entfernt den Block samt Markern und einer davorstehenden Leerzeile.
+// Fehlt zu einem Start-Marker das Ende (von Hand bearbeitet, abgeschnitten), bleibt die
+// Datei unangetastet – sonst ginge alles danach verloren.
+fn entferne_wm_block(inhalt: &str) -> io::Result<String> {
+	let mut zeilen: Vec<&str> = Vec::new();
+	let mut rest = inhalt.lines();
+
+	while let Some(zeile) = rest.next() {
+		if zeile.trim() != WM_MARKER_START {
+			zeilen.push(zeile);
+			continue;
+		}
+		if !rest.by_ref().any(|zeile| zeile.trim() == WM_MARKER_ENDE) {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				format!("{} without {}", WM_MARKER_START, WM_MARKER_ENDE),
+			));
+		}
+		if zeilen.last().is_some_and(|letzte| letzte.trim().is_empty()) {
+			zeilen.pop();
+		}
+	}
+
+	let mut ergebnis = zeilen.join("\n");
+	ergebnis.push('\n');
+	Ok(ergebnis)
+}
+
+// Sichert die Datei, schreibt den neuen Inhalt und lädt bei i3/sway die Konfiguration neu.
+// Ok(true), wenn die Änderung sofort aktiv ist.
+fn schreibe_wm_konfiguration(umgebung: &dyn Umgebung, pfad: &Path, inhalt: &str, desktop: &Desktop) -> io::Result<bool> {
+	let sicherung = PathBuf::from(format!("{}.emoji-picker.bak", pfad.display()));
+	umgebung.kopiere_datei(pfad, &sicherung)?;
+	umgebung.schreibe_datei(pfad, inhalt)?;
+
+	let neu_laden = match desktop {
+		Desktop::Sway	=> Some("swaymsg"),
+		Desktop::I3		=> Some("i3-msg"),
+		_				=> None,
+	};
+
+	Ok(match neu_laden {
+		Some(befehl) => umgebung.ausfuehren(befehl, &["reload"]).is_ok_and(|status| status.erfolg()),
+		// Hyprland lädt geänderte Konfigurationen selbst neu
+		None => *desktop == Desktop::Hyprland,
+	})
+}
+
+fn setup_wm(umgebung: &dyn Umgebung, sprachpaket: Rc<Sprache>, kombi: &Tastenkombi, desktop: &Desktop, eintragen: bool, debug: bool) -> ShortcutErgebnis {
+	let name = desktop_name(desktop);
+	let schnipsel = wm_schnipsel(umgebung, desktop, kombi);
+	println!("🛠 {}: Versuche, Tastenkombi {} zu setzen...", name, kombi.anzeige());
+
+	let pfad = wm_konfiguration(umgebung, desktop);
+
+	// Ohne eigene Konfigurationsdatei nutzt der Fenstermanager seine Vorgabe aus /etc –
+	// eine neue Datei nur mit unserer Zeile würde diese ersetzen, also nur anzeigen
+	let Some(pfad) = pfad.filter(|_| eintragen) else {
+		return ShortcutErgebnis {
+			desktop: name.into(),
+			erfolg: !eintragen,
+			meldung: format!("{}\n\n{}", sprachpaket.setup_wm_snippet, schnipsel),
+		};
+	};
+
+	let inhalt = umgebung.lies_datei(&pfad).unwrap_or_default();
+	if wm_block_zeilen(&inhalt) == wm_zeilen(umgebung, desktop, kombi) {
+		if debug {
+			println!("{}: {:?}", sprachpaket.debug_shortcut_already_done, pfad);
+		}
+		return ShortcutErgebnis {
+			desktop: name.into(),
+			erfolg: true,
+			meldung: sprachpaket.setup_exists.clone(),
+		};
+	}
+
+	// Alten Block (andere Taste) ersetzen, neuen ans Dateiende hängen
+	let mut neu = match entferne_wm_block(&inhalt) {
+		Ok(ohne_block) => ohne_block.trim_end().to_string(),
+		Err(e) => return ShortcutErgebnis {
+			desktop: name.into(),
+			erfolg: false,
+			meldung: format!("{}\n\n{:?}: {}\n\n{}", sprachpaket.setup_fail_text, pfad, e, schnipsel),
+		},
+	};
+	if !neu.is_empty() {
+		neu.push_str("\n\n");
+	}
+	neu.push_str(&schnipsel);
+	neu.push('\n');
+
+	match schreibe_wm_konfiguration(umgebung, &pfad, &neu, desktop) {
+		Ok(aktiv) => {
+			let mut meldung = sprachpaket.setup_done_wm
+				.replace("{key}", &kombi.anzeige())
+				.replace("{file}", &pfad.display().to_string());
+			if !aktiv {
+				meldung = format!("{}\n\n{}", meldung, sprachpaket.setup_wm_restart);
+			}
+			ShortcutErgebnis {
+				desktop: name.into(),
+				erfolg: true,
+				meldung,
+			}
+		}
+		Err(e) => ShortcutErgebnis {
+			desktop: name.into(),
+			erfolg: false,
+			meldung: format!("{}\n\n{:?}: {}\n\n{}", sprachpaket.setup_fail_text, pfad, e, schnipsel),
+		},
+	}
+}
+
+// Wayland-Compositor ohne eigenes Backend: Tastenkürzel über org.freedesktop.portal.GlobalShortcuts.
+// Das Kürzel wirkt nur, solange die Portal-Sitzung (und damit der Picker) im Hintergrund läuft.
+// Die Taste ist dort nur ein Vorschlag; der Desktop kann nachfragen oder eine andere vergeben.
+fn setup_portal(umgebung: &dyn Umgebung, sprachpaket: Rc<Sprache>, kombi: &Tastenkombi, debug: bool) -> Einrichtung {
+	let trigger = kombi.portal();
+	println!("🛠 Portal: Versuche, Tastenkombi {} zu setzen...", trigger);
+
+	let gebunden = portal::gebundene_taste();
+	if gebunden.as_deref() == Some(trigger.as_str()) {
+		if debug {
+			println!("{}: {:?}", sprachpaket.debug_shortcut_already_done, portal::aktive_sitzung());
+		}
+		return Einrichtung::Fertig(ShortcutErgebnis {
+			desktop: "Wayland (Portal)".into(),
+			erfolg: true,
+			meldung: sprachpaket.setup_exists.clone(),
+		});
+	}
+
+	if umgebung.ist_trockenlauf() {
+		if let Some(sitzung) = portal::aktive_sitzung() {
+			umgebung.melde(&format!("# D-Bus org.freedesktop.portal.Session Close {}", sitzung));
+		}
+		umgebung.melde(&format!("# D-Bus org.freedesktop.portal.GlobalShortcuts BindShortcuts '{}' preferred_trigger={}", portal::SHORTCUT_ID, trigger));
+		return Einrichtung::Fertig(ShortcutErgebnis {
+			desktop: "Wayland (Portal)".into(),
+			erfolg: true,
+			meldung: sprachpaket.setup_done_portal.clone(),
+		});
+	}
+
+	// Laufende Sitzung mit anderer Taste: der Dienst legt sie mit der neuen Taste neu an
+	if gebunden.is_some()
+		&& let Some(rueckmeldung) = portal::binde_neu(trigger.clone())
+	{
+		return Einrichtung::Portal(PortalEinrichtung {
+			rueckmeldung,
+			sprachpaket,
+			beginn: Instant::now(),
+		});
+	}
+
+	let conn = match umgebung.sitzungsbus() {
+		Ok(conn) => conn,
+		Err(e) => return Einrichtung::Fertig(ShortcutErgebnis {
+			desktop: "Wayland (Portal)".into(),
+			erfolg: false,
+			meldung: format!("{}\n\nD-Bus: {}", sprachpaket.setup_fail_portal, e),
+		}),
+	};
+
+	// Der Dienst läuft nach der Einrichtung im eigenen Thread weiter und meldet Aktivierungen
+	let (rueckmeldung_tx, rueckmeldung_rx) = channel();
+	let aktivierung = portal::aktivierung();
+	std::thread::spawn(move || {
+		portal::starte_portal_dienst(conn, aktivierung, rueckmeldung_tx, trigger, debug);
+	});
+
+	Einrichtung::Portal(PortalEinrichtung {
+		rueckmeldung: rueckmeldung_rx,
+		sprachpaket,
+		beginn: Instant::now(),
+	})
+}
+
+// Großzügig warten: der Desktop fragt beim Anmelden ggf. per Dialog nach
+const PORTAL_WARTEZEIT: Duration = Duration::from_secs(130);
+
+// Beim Portal steht das Ergebnis erst fest, wenn der Desktop geantwortet hat.
+// Der Setup-Dialog fragt es per Timer ab, die Kommandozeile wartet blockierend.
+enum Einrichtung {
+	Fertig(ShortcutErgebnis),
+	Portal(PortalEinrichtung),
+}
+
+impl Einrichtung {
+	fn warte(self) -> ShortcutErgebnis {
+		match self {
+			Einrichtung::Fertig(ergebnis) => ergebnis,
+			Einrichtung::Portal(portal) => portal.warte(),
+		}
+	}
+}
+
+struct PortalEinrichtung {
+	rueckmeldung: Receiver<Result<String, String>>,
+	sprachpaket: Rc<Sprache>,
+	beginn: Instant,
+}
+
+impl PortalEinrichtung {
+	// None, solange der Desktop noch nicht geantwortet hat
+	fn ergebnis(&self) -> Option<ShortcutErgebnis> {
+		match self.rueckmeldung.try_recv() {
+			Ok(antwort) => Some(self.als_ergebnis(Some(antwort))),
+			Err(TryRecvError::Empty) if self.beginn.elapsed() < PORTAL_WARTEZEIT => None,
+			Err(_) => Some(self.als_ergebnis(None)),
+		}
+	}
+
+	fn warte(self) -> ShortcutErgebnis {
+		let rest = PORTAL_WARTEZEIT.saturating_sub(self.beginn.elapsed());
+		let antwort = self.rueckmeldung.recv_timeout(rest).ok();
+		self.als_ergebnis(antwort)
+	}
+
+	fn als_ergebnis(&self, antwort: Option<Result<String, String>>) -> ShortcutErgebnis {
+		let (erfolg, meldung) = match antwort {
+			Some(Ok(_sitzung)) => (true, self.sprachpaket.setup_done_portal.clone()),
+			Some(Err(e)) => (false, format!("{}\n\n{}", self.sprachpaket.setup_fail_portal, e)),
+			None => (false, self.sprachpaket.setup_fail_portal.clone()),
+		};
+		ShortcutErgebnis {
+			desktop: "Wayland (Portal)".into(),
+			erfolg,
+			meldung,
+		}
+	}
+}
+
+
+// ╔══════════════════════════════════════════════════════════════╗
+// ║      emoji-picker shortcut status | install | remove         ║
+// ╚══════════════════════════════════════════════════════════════╝
+//
+//   status   → erkannter Desktop + Tasten aller emoji-picker-Einträge
+//   install  → setup_shortcut() mit der Tastenkombi aus settings.ini
+//   remove   → entfernt genau die Einträge mit Befehl emoji-picker,
+//              fremde Tastenkombinationen bleiben unberührt
+//   snippet  → nur die Zeilen für sway, Hyprland, i3, river oder bspwm ausgeben
+//   --dry-run → ändernde Befehle nur ausgeben (install ist dann Standard)
+
+#[derive(Debug)]
+pub struct ShortcutStatus {
+	pub desktop: String,
+	pub tasten: Vec<String>,
+}
+
+pub fn shortcut_befehl(umgebung: &dyn Umgebung, aktion: ShortcutAktion, trocken: bool, sprachpaket: Rc<Sprache>, debug: bool) -> i32 {
+	let trockenlauf = Trockenlauf { echt: umgebung };
+	let umgebung: &dyn Umgebung = if trocken { &trockenlauf } else { umgebung };
+
+	match aktion {
+		ShortcutAktion::Status => {
+			let status = shortcut_status(umgebung);
+			println!("Desktop:  {}", status.desktop);
+			if status.tasten.is_empty() {
+				println!("Shortcut: –");
+				return 1;
+			}
+			for taste in &status.tasten {
+				println!("Shortcut: {}", taste);
+			}
+			0
+		}
+		ShortcutAktion::Install | ShortcutAktion::Remove => {
+			let ergebnis = if aktion == ShortcutAktion::Install {
+				// Trockenlauf: settings.ini weder anlegen noch migrieren
+				let einstellungen = if trocken { settings::lies_settings() } else { settings::lade_settings() };
+				let kombi = tastenkombi::aus_einstellung(&einstellungen.tastenkombi.borrow());
+				let ergebnis = setup_shortcut(umgebung, Rc::clone(&sprachpaket), &kombi, debug);
+
+				// Wie beim Setup-Dialog: danach nicht erneut beim Start fragen
+				if !trocken {
+					einstellungen.setup_erledigt.set(true);
+					if let Err(e) = settings::speichere_settings(&einstellungen) {
+						eprintln!("❌ {}: {}", sprachpaket.settings_save_failed, e);
+					}
+				}
+				ergebnis
+			} else {
+				entferne_shortcut(umgebung, Rc::clone(&sprachpaket), debug)
+			};
+
+			println!(
+				"{} - {}\n{}",
+				ergebnis.desktop,
+				if ergebnis.erfolg { &sprachpaket.setup_done } else { &sprachpaket.setup_fail },
+				ergebnis.meldung
+			);
+			// Die Portal-Sitzung endet mit diesem Prozess; der Picker meldet sie beim Start neu an
+			if aktion == ShortcutAktion::Install && ergebnis.erfolg && !trocken && portal::aktive_sitzung().is_some() {
+				println!("\n{}", sprachpaket.setup_portal_cli);
+			}
+			if ergebnis.erfolg { 0 } else { 1 }
+		}
+		ShortcutAktion::Snippet => {
+			let desktop = detect_desktop(umgebung);
+			let kombi = tastenkombi::aus_einstellung(&settings::lies_settings().tastenkombi.borrow());
+			let zeilen = wm_zeilen(umgebung, &desktop, &kombi);
+			if zeilen.is_empty() {
+				eprintln!("❌ {}: no window manager config snippet (sway, Hyprland, i3, river, bspwm)", desktop_name(&desktop));
+				return 1;
+			}
+			println!("{}", wm_schnipsel(umgebung, &desktop, &kombi));
+			0
+		}
+	}
+}
+
+fn desktop_name(desktop: &Desktop) -> &'static str {
+	match desktop {
+		Desktop::Cinnamon	=> "Cinnamon",
+		Desktop::Xfce		=> "XFCE",
+		Desktop::Mate		=> "MATE",
+		Desktop::Kde		=> "KDE",
+		Desktop::Gnome		=> "GNOME",
+		Desktop::Budgie		=> "Budgie",
+		Desktop::Pantheon	=> "Pantheon",
+		Desktop::Lxqt		=> "LXQt",
+		Desktop::Sway		=> "sway",
+		Desktop::Hyprland	=> "Hyprland",
+		Desktop::I3			=> "i3",
+		Desktop::River		=> "river",
+		Desktop::Bspwm		=> "bspwm",
+		Desktop::Unbekannt	=> "Unbekannt",
+	}
+}
+
+const CINNAMON_SCHEMA: &str = "org.cinnamon.desktop.keybindings";
+
+fn cinnamon_eintrag(eintrag: &str) -> String {
+	format!("{}.custom-keybinding:/org/cinnamon/desktop/keybindings/custom-keybindings/{}/", CINNAMON_SCHEMA, eintrag)
+}
+
+fn gnome_eintrag(pfad: &str) -> String {
+	format!("{}:{}", GSD_EINTRAG, pfad)
+}
+
+fn mate_eintrag(eintrag: &str) -> String {
+	format!("{}:{}{}/", MATE_EINTRAG, MATE_PFAD, eintrag)
+}
+
+// Unterordner von MATE_PFAD ("custom0/", "custom1/", ...) ohne Schrägstrich
+fn mate_eintraege(umgebung: &dyn Umgebung) -> Vec<String> {
+	umgebung
+		.abfrage("dconf", &["list", MATE_PFAD])
+		.map(|output| {
+			output.stdout
+				.lines()
+				.filter_map(|zeile| zeile.strip_suffix('/'))
+				.map(|zeile| zeile.to_string())
+				.collect()
+		})
+		.unwrap_or_default()
+}
+
+// Einträge einer gsettings-Liste, deren Befehl emoji-picker ist
+fn eigene_gsettings_eintraege(umgebung: &dyn Umgebung, liste: &[String], schema: impl Fn(&str) -> String, befehl: &str) -> Vec<String> {
+	liste
+		.iter()
+		.filter(|eintrag| lies_gsettings(umgebung, &schema(eintrag), befehl).is_some_and(|wert| wert.contains("emoji-picker")))
+		.cloned()
+		.collect()
+}
+
+fn marco_eigene_plaetze(umgebung: &dyn Umgebung) -> Vec<u32> {
+	(1..=12)
+		.filter(|nr| {
+			lies_gsettings(umgebung, "org.mate.Marco.keybinding-commands", &format!("command-{}", nr))
+				.is_some_and(|wert| wert.contains("emoji-picker"))
+		})
+		.collect()
+}
+
+fn kde_gruppe(umgebung: &dyn Umgebung) -> &'static str {
+	if ist_plasma6(umgebung) { "services][emoji-picker.desktop" } else { "emoji-picker.desktop" }
+}
+
+fn kde_konfiguration(umgebung: &dyn Umgebung) -> PathBuf {
+	umgebung.xdg_konfig().join("kglobalshortcutsrc")
+}
+
+fn lxqt_konfiguration(umgebung: &dyn Umgebung) -> PathBuf {
+	umgebung
+		.xdg_konfig()
+		.join("lxqt")
+		.join("globalkeyshortcuts.conf")
+}
+
+pub fn shortcut_status(umgebung: &dyn Umgebung) -> ShortcutStatus {
+	let desktop = detect_desktop(umgebung);
+	let ohne_quotes = |wert: String| wert.trim_matches('\'').to_string();
+
+	let tasten = match desktop {
+		Desktop::Cinnamon => {
+			let liste = lies_gsettings(umgebung, CINNAMON_SCHEMA, "custom-list").map(|raw| zerlege_gsettings_liste(&raw)).unwrap_or_default();
+			eigene_gsettings_eintraege(umgebung, &liste, cinnamon_eintrag, "command")
+				.iter()
+				.flat_map(|eintrag| zerlege_gsettings_liste(&lies_gsettings(umgebung, &cinnamon_eintrag(eintrag), "binding").unwrap_or_default()))
+				.collect()
+		}
+		Desktop::Gnome | Desktop::Budgie | Desktop::Pantheon => {
+			let liste = lies_gsettings(umgebung, GSD_SCHEMA, "custom-keybindings").map(|raw| zerlege_gsettings_liste(&raw)).unwrap_or_default();
+			eigene_gsettings_eintraege(umgebung, &liste, gnome_eintrag, "command")
+				.iter()
+				.filter_map(|pfad| lies_gsettings(umgebung, &gnome_eintrag(pfad), "binding").map(ohne_quotes))
+				.collect()
+		}
+		Desktop::Mate => {
+			let mut tasten: Vec<String> = eigene_gsettings_eintraege(umgebung, &mate_eintraege(umgebung), mate_eintrag, "action")
+				.iter()
+				.filter_map(|eintrag| lies_gsettings(umgebung, &mate_eintrag(eintrag), "binding").map(ohne_quotes))
+				.collect();
+			tasten.extend(marco_eigene_plaetze(umgebung).iter().filter_map(|nr| {
+				lies_gsettings(umgebung, "org.mate.Marco.global-keybindings", &format!("run-command-{}", nr)).map(ohne_quotes)
+			}));
+			tasten
+		}
+		Desktop::Xfce => xfce_eigene_properties(umgebung)
+			.iter()
+			.filter_map(|property| property.strip_prefix("/commands/custom/"))
+			.map(|taste| taste.to_string())
+			.collect(),
+		Desktop::Kde => {
+			let inhalt = umgebung.lies_datei(&kde_konfiguration(umgebung)).unwrap_or_default();
+			let kopf = format!("[{}]", kde_gruppe(umgebung));
+			inhalt
+				.lines()
+				.skip_while(|zeile| zeile.trim() != kopf)
+				.skip(1)
+				.take_while(|zeile| !zeile.trim_start().starts_with('['))
+				.filter_map(|zeile| zeile.trim().strip_prefix("_launch="))
+				.filter_map(|wert| wert.split(',').next())
+				.filter(|taste| !taste.is_empty() && *taste != "none")
+				.map(|taste| taste.to_string())
+				.collect()
+		}
+		Desktop::Lxqt => {
+			let inhalt = umgebung.lies_datei(&lxqt_konfiguration(umgebung)).unwrap_or_default();
+			lxqt_eigene_gruppen(&inhalt).into_iter().map(|(_, taste, _)| taste).collect()
+		}
+		Desktop::Sway | Desktop::Hyprland | Desktop::I3 | Desktop::River | Desktop::Bspwm => wm_konfiguration(umgebung, &desktop)
+			.and_then(|pfad| umgebung.lies_datei(&pfad).ok())
+			.map(|inhalt| wm_block_zeilen(&inhalt))
+			.unwrap_or_default(),
+		Desktop::Unbekannt => portal::gespeicherte_sitzung()
+			.map(|sitzung| vec![format!("xdg-desktop-portal ({})", sitzung)])
+			.unwrap_or_default(),
+	};
+
+	ShortcutStatus {
+		desktop: desktop_name(&desktop).into(),
+		tasten,
+	}
+}
+
+pub fn entferne_shortcut(umgebung: &dyn Umgebung, sprachpaket: Rc<Sprache>, debug: bool) -> ShortcutErgebnis {
+	let desktop = detect_desktop(umgebung);
+	let name = desktop_name(&desktop);
+
+	let (gefunden, erfolg) = match desktop {
+		Desktop::Cinnamon => {
+			let liste = lies_gsettings(umgebung, CINNAMON_SCHEMA, "custom-list").map(|raw| zerlege_gsettings_liste(&raw)).unwrap_or_default();
+			let eigene = eigene_gsettings_eintraege(umgebung, &liste, cinnamon_eintrag, "command");
+			let erfolg = entferne_gsettings_eintraege(umgebung, &liste, &eigene, CINNAMON_SCHEMA, "custom-list", cinnamon_eintrag, &sprachpaket);
+			(!eigene.is_empty(), erfolg)
+		}
+		Desktop::Gnome | Desktop::Budgie | Desktop::Pantheon => {
+			let liste = lies_gsettings(umgebung, GSD_SCHEMA, "custom-keybindings").map(|raw| zerlege_gsettings_liste(&raw)).unwrap_or_default();
+			let eigene = eigene_gsettings_eintraege(umgebung, &liste, gnome_eintrag, "command");
+			let erfolg = entferne_gsettings_eintraege(umgebung, &liste, &eigene, GSD_SCHEMA, "custom-keybindings", gnome_eintrag, &sprachpaket);
+			(!eigene.is_empty(), erfolg)
+		}
+		Desktop::Mate => {
+			let eigene = eigene_gsettings_eintraege(umgebung, &mate_eintraege(umgebung), mate_eintrag, "action");
+			let plaetze = marco_eigene_plaetze(umgebung);
+			let mut erfolg = true;
+
+			// Ohne Werte verschwindet der dconf-Ordner customN/ von selbst
+			for eintrag in &eigene {
+				let schema = mate_eintrag(eintrag);
+				for schluessel in ["name", "action", "binding"] {
+					let reset = ["reset", schema.as_str(), schluessel];
+					erfolg &= apply_gsettings(umgebung, &[("gsettings", &reset[..])], Rc::clone(&sprachpaket));
+				}
+			}
+			for nr in &plaetze {
+				let befehl = format!("command-{}", nr);
+				let taste = format!("run-command-{}", nr);
+				let marco_command	= ["reset", "org.mate.Marco.keybinding-commands", &befehl];
+				let marco_binding	= ["reset", "org.mate.Marco.global-keybindings", &taste];
+				erfolg &= apply_gsettings(umgebung,
+					&[("gsettings", &marco_command[..]), ("gsettings", &marco_binding[..])],
+					Rc::clone(&sprachpaket),
+				);
+			}
+			(!eigene.is_empty() || !plaetze.is_empty(), erfolg)
+		}
+		Desktop::Xfce => {
+			let eigene = xfce_eigene_properties(umgebung);
+			let mut erfolg = true;
+			for property in &eigene {
+				let reset = ["--channel", XFCE_KANAL, "--property", property.as_str(), "--reset"];
+				erfolg &= apply_gsettings(umgebung, &[("xfconf-query", &reset[..])], Rc::clone(&sprachpaket));
+			}
+			(!eigene.is_empty(), erfolg)
+		}
+		Desktop::Kde => {
+			if let Err(e) = kglobalaccel_abmelden(umgebung)
+				&& debug
+			{
+				println!("❌ {} kglobalaccel: {}", sprachpaket.debug_shortcut_apply_error, e);
+			}
+
+			let pfad = kde_konfiguration(umgebung);
+			let inhalt = umgebung.lies_datei(&pfad).unwrap_or_default();
+			let service = kde_service_datei(umgebung);
+			let in_datei = inhalt.lines().any(|zeile| zeile.trim() == format!("[{}]", kde_gruppe(umgebung)));
+
+			let mut ergebnis = Ok(());
+			if in_datei {
+				ergebnis = umgebung.schreibe_datei(&pfad, &entferne_ini_gruppe(&inhalt, kde_gruppe(umgebung)));
+			}
+			let service_vorhanden = umgebung.existiert(&service);
+			if service_vorhanden {
+				ergebnis = ergebnis.and_then(|_| umgebung.entferne_datei(&service));
+			}
+			(in_datei || service_vorhanden, ergebnis.is_ok())
+		}
+		Desktop::Lxqt => {
+			let pfad = lxqt_konfiguration(umgebung);
+			let inhalt = umgebung.lies_datei(&pfad).unwrap_or_default();
+			let eigene = lxqt_eigene_gruppen(&inhalt);
+
+			// Laufender Daemon würde die Datei sonst mit seinem Stand überschreiben
+			let ids: Vec<u64> = eigene.iter().filter_map(|(_, _, id)| *id).collect();
+			if let Err(e) = lxqt_abmelden(umgebung, &ids)
+				&& debug
+			{
+				println!("❌ {} lxqt-globalkeysd: {}", sprachpaket.debug_shortcut_apply_error, e);
+			}
+
+			let mut neu = inhalt.clone();
+			for (gruppe, _, _) in &eigene {
+				neu = entferne_ini_gruppe(&neu, gruppe);
+			}
+			let erfolg = eigene.is_empty() || umgebung.schreibe_datei(&pfad, &neu).is_ok();
+			(!eigene.is_empty(), erfolg)
+		}
+		Desktop::Sway | Desktop::Hyprland | Desktop::I3 | Desktop::River | Desktop::Bspwm => {
+			match wm_konfiguration(umgebung, &desktop) {
+				Some(pfad) => {
+					let inhalt = umgebung.lies_datei(&pfad).unwrap_or_default();
+					let gefunden = !wm_block_zeilen(&inhalt).is_empty();
+					let erfolg = !gefunden
+						|| entferne_wm_block(&inhalt).and_then(|neu| schreibe_wm_konfiguration(umgebung, &pfad, &neu, &desktop)).is_ok();
+					(gefunden, erfolg)
+				}
+				None => (false, true),
+			}
+		}
+		Desktop::Unbekannt => {
+			// Eine laufende Portal-Sitzung endet mit dem Prozess; hier nur nicht mehr erneuern
+			let datei = portal::sitzungsdatei();
+			let gefunden = umgebung.existiert(&datei);
+			(gefunden, !gefunden || umgebung.entferne_datei(&datei).is_ok())
+		}
+	};
+
+	ShortcutErgebnis {
+		desktop: name.into(),
+		erfolg,
+		meldung: if !gefunden {
+			sprachpaket.setup_none.clone()
+		} else if erfolg {
+			sprachpaket.setup_removed.clone()
+		} else {
+			sprachpaket.setup_fail_text.clone()
+		},
+	}
+}
+
+// Eigene Einträge aus der Liste nehmen und ihre Werte zurücksetzen (Cinnamon, GNOME)
+fn entferne_gsettings_eintraege(
+	umgebung: &dyn Umgebung,
+	liste: &[String],
+	eigene: &[String],
+	schema: &str,
+	listen_schluessel: &str,
+	eintrag_schema: impl Fn(&str) -> String,
+	sprachpaket: &Rc<Sprache>,
+) -> bool {
+	if eigene.is_empty() {
+		return true;
+	}
+
+	let rest: Vec<String> = liste
+		.iter()
+		.filter(|eintrag| !eigene.contains(eintrag))
+		.map(|eintrag| format!("'{}'", eintrag))
+		.collect();
+	let list_string = format!("[{}]", rest.join(", "));
+
+	// Erst aus der Liste nehmen, dann die Werte leeren
+	let gsettings_list = ["set", schema, listen_schluessel, &list_string];
+	let mut erfolg = apply_gsettings(umgebung, &[("gsettings", &gsettings_list[..])], Rc::clone(sprachpaket));
+
+	for eintrag in eigene {
+		let pfad = eintrag_schema(eintrag);
+		for schluessel in ["name", "command", "binding"] {
+			let reset = ["reset", pfad.as_str(), schluessel];
+			erfolg &= apply_gsettings(umgebung, &[("gsettings", &reset[..])], Rc::clone(sprachpaket));
+		}
+	}
+	erfolg
+}
+
+fn kglobalaccel_abmelden(umgebung: &dyn Umgebung) -> Result<(), dbus::Error> {
+	if umgebung.ist_trockenlauf() {
+		umgebung.melde(&format!("# D-Bus org.kde.kglobalaccel unRegister {:?}", KDE_AKTION));
+		return Ok(());
+	}
+
+	let conn = umgebung.sitzungsbus()?;
+	let proxy = conn.with_proxy("org.kde.kglobalaccel", "/kglobalaccel", Duration::from_secs(2));
+	proxy.method_call::<(bool,), _, _, _>(KGLOBALACCEL, "unRegister", (kde_aktion(),))?;
+	Ok(())
+}
+
+fn lxqt_abmelden(umgebung: &dyn Umgebung, ids: &[u64]) -> Result<(), dbus::Error> {
+	if umgebung.ist_trockenlauf() {
+		for id in ids {
+			umgebung.melde(&format!("# D-Bus {} removeAction {}", LXQT_DIENST, id));
+		}
+		return Ok(());
+	}
+
+	let conn = umgebung.sitzungsbus()?;
+	let proxy = conn.with_proxy(LXQT_DIENST, "/native", Duration::from_secs(2));
+	for id in ids {
+		proxy.method_call::<(bool,), _, _, _>(LXQT_NATIVE, "removeAction", (*id,))?;
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::umgebung::attrappe::{Attrappe, HEIM};
+	use crate::umgebung::testbus::Testbus;
+	use dbus::Message;
+	use std::sync::{Arc, Mutex};
+
+	fn sprache() -> Rc<Sprache> {
+		Rc::new(Sprache::aus_auswahl("en", false))
+	}
+
+	fn super_punkt() -> Tastenkombi {
+		Tastenkombi::default()
+	}
+
+	fn super_e() -> Tastenkombi {
+		Tastenkombi::aus_gtk("<Super>e").unwrap()
+	}
+
+	fn konfig(datei: &str) -> PathBuf {
+		Path::new(HEIM).join(".config").join(datei)
+	}
+
+	fn gemeldet(umgebung: &Attrappe, zeile: &str) -> bool {
+		umgebung.meldungen.borrow().iter().any(|gemeldet| gemeldet == zeile)
+	}
+
+	#[test]
+	fn desktop_erkennen() {
+		let faelle = [
+			(Attrappe::neu("X-Cinnamon"), Desktop::Cinnamon),
+			(Attrappe::neu("Budgie:GNOME"), Desktop::Budgie),
+			(Attrappe::neu("ubuntu:GNOME"), Desktop::Gnome),
+			(Attrappe::neu("Pantheon"), Desktop::Pantheon),
+			(Attrappe::neu("KDE"), Desktop::Kde),
+			(Attrappe::neu("LXQt"), Desktop::Lxqt),
+			(Attrappe::neu("XFCE"), Desktop::Xfce),
+			(Attrappe::neu("MATE"), Desktop::Mate),
+			(Attrappe::neu("sway"), Desktop::Sway),
+			(Attrappe::neu("").mit_variable("SWAYSOCK", "/run/sway.sock").mit_variable("I3SOCK", "/run/sway.sock"), Desktop::Sway),
+			(Attrappe::neu("").mit_variable("I3SOCK", "/run/i3.sock"), Desktop::I3),
+			(Attrappe::neu("Hyprland"), Desktop::Hyprland),
+			(Attrappe::neu("").mit_variable("XDG_SESSION_DESKTOP", "river"), Desktop::River),
+			(Attrappe::neu("").mit_variable("BSPWM_SOCKET", "/tmp/bspwm.sock"), Desktop::Bspwm),
+			(Attrappe::neu(""), Desktop::Unbekannt),
+		];
+		for (umgebung, erwartet) in faelle {
+			assert_eq!(detect_desktop(&umgebung), erwartet);
+		}
+	}
+
+	#[test]
+	fn cinnamon_neuer_eintrag_neben_fremdem() {
+		let umgebung = Attrappe::neu("X-Cinnamon")
+			.mit_gsettings(CINNAMON_SCHEMA, "custom-list", "['custom0']")
+			.mit_gsettings(&cinnamon_eintrag("custom0"), "command", "firefox")
+			.mit_gsettings(&cinnamon_eintrag("custom0"), "binding", "['<Super>period']");
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+
+		assert!(ergebnis.erfolg);
+		assert_eq!(umgebung.gsettings(CINNAMON_SCHEMA, "custom-list").unwrap(), "['custom0', 'custom1']");
+		assert_eq!(umgebung.gsettings(&cinnamon_eintrag("custom0"), "command").unwrap(), "'firefox'");
+		assert_eq!(umgebung.gsettings(&cinnamon_eintrag("custom1"), "command").unwrap(), "'emoji-picker'");
+		assert_eq!(umgebung.gsettings(&cinnamon_eintrag("custom1"), "binding").unwrap(), "['<Super>period']");
+		assert_eq!(shortcut_status(&umgebung).tasten, ["<Super>period"]);
+
+		// Zweiter Lauf ändert nichts mehr
+		umgebung.befehle.borrow_mut().clear();
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+		assert_eq!(ergebnis.meldung, sprache().setup_exists);
+		assert!(umgebung.befehle.borrow().is_empty());
+
+		let ergebnis = entferne_shortcut(&umgebung, sprache(), false);
+		assert!(ergebnis.erfolg);
+		assert_eq!(umgebung.gsettings(CINNAMON_SCHEMA, "custom-list").unwrap(), "['custom0']");
+		assert_eq!(umgebung.gsettings(&cinnamon_eintrag("custom1"), "command"), None);
+	}
+
+	#[test]
+	fn gnome_konflikt_mit_fremdem_eintrag() {
+		let fremd = format!("{}custom0/", GSD_LISTE_PFAD);
+		let umgebung = Attrappe::neu("GNOME")
+			.mit_gsettings(GSD_SCHEMA, "custom-keybindings", &format!("['{}']", fremd))
+			.mit_gsettings(&gnome_eintrag(&fremd), "name", "Terminal")
+			.mit_gsettings(&gnome_eintrag(&fremd), "command", "gnome-terminal")
+			.mit_gsettings(&gnome_eintrag(&fremd), "binding", "<Super>period");
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+
+		assert!(!ergebnis.erfolg);
+		assert!(ergebnis.meldung.starts_with(&sprache().setup_conflict));
+		assert!(ergebnis.meldung.contains("Terminal (gnome-terminal)"));
+		assert!(umgebung.befehle.borrow().is_empty());
+	}
+
+	#[test]
+	fn gnome_alle_plaetze_belegt() {
+		let liste = (0..50)
+			.map(|i| format!("'{}custom{}/'", GSD_LISTE_PFAD, i))
+			.collect::<Vec<_>>()
+			.join(", ");
+		let erster = format!("{}custom0/", GSD_LISTE_PFAD);
+		let umgebung = Attrappe::neu("GNOME")
+			.mit_gsettings(GSD_SCHEMA, "custom-keybindings", &format!("[{}]", liste))
+			.mit_gsettings(&gnome_eintrag(&erster), "command", "gnome-terminal");
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+
+		assert!(!ergebnis.erfolg);
+		assert_eq!(ergebnis.meldung, sprache().setup_no_free_slot);
+		assert!(umgebung.befehle.borrow().is_empty());
+		assert_eq!(umgebung.gsettings(&gnome_eintrag(&erster), "command").unwrap(), "'gnome-terminal'");
+	}
+
+	#[test]
+	fn gnome_eigener_eintrag_wird_umgestellt() {
+		let eigener = format!("{}custom3/", GSD_LISTE_PFAD);
+		let umgebung = Attrappe::neu("GNOME")
+			.mit_gsettings(GSD_SCHEMA, "custom-keybindings", &format!("['{}']", eigener))
+			.mit_gsettings(&gnome_eintrag(&eigener), "command", "emoji-picker")
+			.mit_gsettings(&gnome_eintrag(&eigener), "binding", "<Super>e");
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+
+		assert!(ergebnis.erfolg);
+		assert_eq!(umgebung.gsettings(GSD_SCHEMA, "custom-keybindings").unwrap(), format!("['{}']", eigener));
+		assert_eq!(umgebung.gsettings(&gnome_eintrag(&eigener), "binding").unwrap(), "'<Super>period'");
+		assert_eq!(shortcut_status(&umgebung).tasten, ["<Super>period"]);
+
+		let ergebnis = entferne_shortcut(&umgebung, sprache(), false);
+		assert!(ergebnis.erfolg);
+		assert_eq!(umgebung.gsettings(GSD_SCHEMA, "custom-keybindings").unwrap(), "[]");
+		assert!(shortcut_status(&umgebung).tasten.is_empty());
+	}
+
+	#[test]
+	fn xfce_alte_taste_wird_entfernt() {
+		let umgebung = Attrappe::neu("XFCE")
+			.mit_xfconf("/commands/custom/<Super>e", "emoji-picker")
+			.mit_xfconf("/commands/custom/<Alt>F2", "xfce4-appfinder");
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+
+		assert!(ergebnis.erfolg);
+		assert_eq!(umgebung.xfconf("/commands/custom/<Super>period").unwrap(), "emoji-picker");
+		assert_eq!(umgebung.xfconf("/commands/custom/<Super>e"), None);
+		assert_eq!(umgebung.xfconf("/commands/custom/<Alt>F2").unwrap(), "xfce4-appfinder");
+		assert_eq!(shortcut_status(&umgebung).tasten, ["<Super>period"]);
+
+		entferne_shortcut(&umgebung, sprache(), false);
+		assert_eq!(umgebung.xfconf("/commands/custom/<Super>period"), None);
+		assert_eq!(umgebung.xfconf("/commands/custom/<Alt>F2").unwrap(), "xfce4-appfinder");
+	}
+
+	#[test]
+	fn xfce_ohne_xfconf_query() {
+		let umgebung = Attrappe::neu("XFCE").ohne_programm("xfconf-query");
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+
+		assert!(!ergebnis.erfolg);
+		assert!(ergebnis.meldung.starts_with(&sprache().setup_fail_xfce_2));
+	}
+
+	#[test]
+	fn mate_ueber_settings_daemon() {
+		let umgebung = Attrappe::neu("MATE")
+			.mit_gsettings(&mate_eintrag("custom0"), "action", "caja");
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+
+		assert!(ergebnis.erfolg);
+		assert_eq!(umgebung.gsettings(&mate_eintrag("custom0"), "action").unwrap(), "'caja'");
+		assert_eq!(umgebung.gsettings(&mate_eintrag("custom1"), "action").unwrap(), "'emoji-picker'");
+		assert_eq!(umgebung.gsettings(&mate_eintrag("custom1"), "binding").unwrap(), "'<Mod4>period'");
+		assert_eq!(shortcut_status(&umgebung).tasten, ["<Mod4>period"]);
+
+		entferne_shortcut(&umgebung, sprache(), false);
+		assert_eq!(umgebung.gsettings(&mate_eintrag("custom1"), "action"), None);
+		assert_eq!(umgebung.gsettings(&mate_eintrag("custom0"), "action").unwrap(), "'caja'");
+	}
+
+	#[test]
+	fn mate_alle_plaetze_belegt() {
+		let umgebung = (0..50).fold(Attrappe::neu("MATE"), |umgebung, i| {
+			umgebung.mit_gsettings(&mate_eintrag(&format!("custom{}", i)), "action", "caja")
+		});
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+
+		assert!(!ergebnis.erfolg);
+		assert_eq!(ergebnis.meldung, sprache().setup_no_free_slot);
+		assert!(umgebung.befehle.borrow().is_empty());
+		assert_eq!(umgebung.gsettings(&mate_eintrag("custom0"), "binding"), None);
+	}
+
+	#[test]
+	fn mate_ohne_daemon_ueber_marco() {
+		let umgebung = Attrappe::neu("MATE")
+			.ohne_schema(MATE_EINTRAG)
+			.mit_gsettings("org.mate.Marco.keybinding-commands", "command-1", "pluma");
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+
+		assert!(ergebnis.erfolg);
+		assert_eq!(umgebung.gsettings("org.mate.Marco.keybinding-commands", "command-1").unwrap(), "'pluma'");
+		assert_eq!(umgebung.gsettings("org.mate.Marco.keybinding-commands", "command-2").unwrap(), "'emoji-picker'");
+		assert_eq!(umgebung.gsettings("org.mate.Marco.global-keybindings", "run-command-2").unwrap(), "'<Mod4>period'");
+		assert_eq!(shortcut_status(&umgebung).tasten, ["<Mod4>period"]);
+
+		// Andere Taste: derselbe Marco-Platz wird umgestellt
+		setup_shortcut(&umgebung, sprache(), &super_e(), false);
+		assert_eq!(umgebung.gsettings("org.mate.Marco.global-keybindings", "run-command-2").unwrap(), "'<Mod4>e'");
+		assert_eq!(umgebung.gsettings("org.mate.Marco.keybinding-commands", "command-3"), None);
+	}
+
+	#[test]
+	fn kde_ohne_kglobalaccel_plasma5() {
+		let umgebung = Attrappe::neu("KDE")
+			.mit_datei(konfig("kglobalshortcutsrc"), "[kwin]\nExpose=Ctrl+F9,Ctrl+F9,Toggle Present Windows\n");
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+
+		assert!(ergebnis.erfolg);
+		assert_eq!(ergebnis.meldung, mit_taste(&sprache().setup_done_kde_file, "Meta+."));
+		assert_eq!(
+			umgebung.datei(konfig("kglobalshortcutsrc")).unwrap(),
+			"[kwin]\nExpose=Ctrl+F9,Ctrl+F9,Toggle Present Windows\n\n[emoji-picker.desktop]\n_launch=Meta+.,none,Emoji Picker\n"
+		);
+		let service = umgebung.datei(kde_service_datei(&umgebung)).unwrap();
+		assert!(service.contains("X-KDE-Shortcuts=Meta+.\n"));
+		assert_eq!(shortcut_status(&umgebung).tasten, ["Meta+."]);
+
+		let ergebnis = entferne_shortcut(&umgebung, sprache(), false);
+		assert!(ergebnis.erfolg);
+		assert_eq!(umgebung.datei(konfig("kglobalshortcutsrc")).unwrap(), "[kwin]\nExpose=Ctrl+F9,Ctrl+F9,Toggle Present Windows\n\n");
+		assert_eq!(umgebung.datei(kde_service_datei(&umgebung)), None);
+	}
+
+	#[test]
+	fn kde_ohne_kglobalaccel_plasma6() {
+		let umgebung = Attrappe::neu("KDE").mit_variable("KDE_SESSION_VERSION", "6");
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_e(), false);
+
+		assert!(ergebnis.erfolg);
+		assert_eq!(
+			umgebung.datei(konfig("kglobalshortcutsrc")).unwrap(),
+			"[services][emoji-picker.desktop]\n_launch=Meta+E\n"
+		);
+		assert_eq!(shortcut_status(&umgebung).tasten, ["Meta+E"]);
+	}
+
+	// Nachgebildetes kglobalaccel: merkt sich die zuletzt gesetzten Qt-Codes
+	fn kglobalaccel(bus: &mut Testbus) -> Arc<Mutex<Vec<i32>>> {
+		let tasten = Arc::new(Mutex::new(Vec::new()));
+		let gemerkt = Arc::clone(&tasten);
+		bus.dienst("org.kde.kglobalaccel", move |nachricht: &Message, _| {
+			let mut tasten = gemerkt.lock().unwrap();
+			match nachricht.member().as_deref() {
+				Some("setForeignShortcutKeys") => {
+					let (_, folgen): (Vec<String>, Vec<(Vec<i32>,)>) = nachricht.read2().unwrap();
+					*tasten = folgen.iter().filter_map(|(folge,)| folge.first().copied()).collect();
+					None
+				}
+				Some("shortcutKeys") => {
+					let folgen: Vec<(Vec<i32>,)> = tasten.iter().map(|code| (vec![*code, 0, 0, 0],)).collect();
+					Some(nachricht.method_return().append1(folgen))
+				}
+				Some("unRegister") => {
+					tasten.clear();
+					Some(nachricht.method_return().append1(true))
+				}
+				_ => None,
+			}
+		});
+		tasten
+	}
+
+	#[test]
+	fn kde_ueber_kglobalaccel() {
+		let Some(mut bus) = Testbus::starte() else {
+			eprintln!("dbus-daemon not found, skipping");
+			return;
+		};
+		let tasten = kglobalaccel(&mut bus);
+		let umgebung = Attrappe::neu("KDE").mit_bus(&bus.adresse);
+		let (qt_code, _) = super_punkt().kde().unwrap();
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+
+		assert!(ergebnis.erfolg);
+		assert_eq!(ergebnis.meldung, mit_taste(&sprache().setup_done_kde, "Meta+."));
+		assert_eq!(*tasten.lock().unwrap(), [qt_code]);
+		assert_eq!(umgebung.datei(konfig("kglobalshortcutsrc")), None);
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+		assert_eq!(ergebnis.meldung, sprache().setup_exists);
+
+		entferne_shortcut(&umgebung, sprache(), false);
+		assert!(tasten.lock().unwrap().is_empty());
+	}
+
+	#[test]
+	fn lxqt_ohne_daemon_in_datei() {
+		let umgebung = Attrappe::neu("LXQt").mit_datei(
+			konfig("lxqt/globalkeyshortcuts.conf"),
+			"[Alt%2BF2.0]\nComment=Run\nEnabled=true\nExec=lxqt-runner\n\n[Meta%2Be.3]\nComment=Emoji Picker\nEnabled=true\nExec=emoji-picker\n",
+		);
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+
+		assert!(ergebnis.erfolg);
+		assert_eq!(
+			umgebung.datei(konfig("lxqt/globalkeyshortcuts.conf")).unwrap(),
+			"[Alt%2BF2.0]\nComment=Run\nEnabled=true\nExec=lxqt-runner\n\n[Meta%2Bperiod.4]\nComment=Emoji Picker\nEnabled=true\nExec=emoji-picker\n"
+		);
+		assert_eq!(shortcut_status(&umgebung).tasten, ["Meta+period"]);
+
+		entferne_shortcut(&umgebung, sprache(), false);
+		assert_eq!(
+			umgebung.datei(konfig("lxqt/globalkeyshortcuts.conf")).unwrap(),
+			"[Alt%2BF2.0]\nComment=Run\nEnabled=true\nExec=lxqt-runner\n\n"
+		);
+	}
+
+	#[test]
+	fn sway_block_eintragen_ersetzen_entfernen() {
+		let umgebung = Attrappe::neu("sway").mit_datei(konfig("sway/config"), "set $mod Mod4\n");
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+
+		assert!(ergebnis.erfolg);
+		assert_eq!(
+			umgebung.datei(konfig("sway/config")).unwrap(),
+			format!("set $mod Mod4\n\n{}\nbindsym Mod4+period exec --no-startup-id emoji-picker\n{}\n", WM_MARKER_START, WM_MARKER_ENDE)
+		);
+		assert_eq!(umgebung.datei(konfig("sway/config.emoji-picker.bak")).unwrap(), "set $mod Mod4\n");
+		assert!(umgebung.befehle.borrow().contains(&"swaymsg reload".to_string()));
+		assert_eq!(shortcut_status(&umgebung).tasten, ["bindsym Mod4+period exec --no-startup-id emoji-picker"]);
+
+		// Andere Taste ersetzt den Block, statt einen zweiten anzuhängen
+		setup_shortcut(&umgebung, sprache(), &super_e(), false);
+		let inhalt = umgebung.datei(konfig("sway/config")).unwrap();
+		assert_eq!(inhalt.matches(WM_MARKER_START).count(), 1);
+		assert_eq!(wm_block_zeilen(&inhalt), ["bindsym Mod4+e exec --no-startup-id emoji-picker"]);
+
+		let ergebnis = entferne_shortcut(&umgebung, sprache(), false);
+		assert!(ergebnis.erfolg);
+		assert_eq!(umgebung.datei(konfig("sway/config")).unwrap(), "set $mod Mod4\n");
+	}
+
+	#[test]
+	fn wm_block_ohne_ende_bleibt_stehen() {
+		let inhalt = format!("set $mod Mod4\n\n{}\nbindsym Mod4+period exec emoji-picker\nbindsym Mod4+Return exec foot\n", WM_MARKER_START);
+		let umgebung = Attrappe::neu("sway").mit_datei(konfig("sway/config"), &inhalt);
+
+		assert!(entferne_wm_block(&inhalt).is_err());
+		assert!(!setup_shortcut(&umgebung, sprache(), &super_e(), false).erfolg);
+		assert!(!entferne_shortcut(&umgebung, sprache(), false).erfolg);
+		assert_eq!(umgebung.datei(konfig("sway/config")).unwrap(), inhalt);
+		assert_eq!(umgebung.datei(konfig("sway/config.emoji-picker.bak")), None);
+	}
+
+	#[test]
+	fn fenstermanager_ohne_konfiguration_nur_schnipsel() {
+		let umgebung = Attrappe::neu("Hyprland");
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+
+		assert!(!ergebnis.erfolg);
+		assert!(ergebnis.meldung.contains("bind = SUPER, period, exec, emoji-picker"));
+		assert!(!umgebung.existiert(&konfig("hypr")));
+	}
+
+	#[test]
+	fn eigener_konfigurationsordner_im_aufruf() {
+		let aufruf = ["/opt/emoji picker/emoji-picker", "--config-dir", "/home/test/emoji"];
+		let befehl = "\"/opt/emoji picker/emoji-picker\" --config-dir /home/test/emoji";
+
+		let umgebung = Attrappe::neu("GNOME").mit_aufruf(&aufruf);
+		assert!(setup_shortcut(&umgebung, sprache(), &super_punkt(), false).erfolg);
+		let eintrag = gnome_eintrag(&format!("{}custom0/", GSD_LISTE_PFAD));
+		assert_eq!(umgebung.gsettings(&eintrag, "command").unwrap(), format!("'{}'", befehl));
+
+		let umgebung = Attrappe::neu("XFCE").mit_aufruf(&aufruf);
+		assert!(setup_shortcut(&umgebung, sprache(), &super_punkt(), false).erfolg);
+		assert_eq!(umgebung.xfconf("/commands/custom/<Super>period").unwrap(), befehl);
+		assert_eq!(shortcut_status(&umgebung).tasten, ["<Super>period"]);
+
+		// river: spawn bekommt die ganze Befehlszeile als ein Argument
+		let umgebung = Attrappe::neu("river").mit_datei(konfig("river/init"), "#!/bin/sh\n").mit_aufruf(&aufruf);
+		assert!(setup_shortcut(&umgebung, sprache(), &super_punkt(), false).erfolg);
+		assert_eq!(
+			wm_block_zeilen(&umgebung.datei(konfig("river/init")).unwrap()),
+			[format!("riverctl map normal Super period spawn '{}'", befehl)]
+		);
+	}
+
+	#[test]
+	fn unbekannter_desktop_ohne_portal() {
+		let umgebung = Attrappe::neu("");
+
+		let ergebnis = setup_shortcut(&umgebung, sprache(), &super_punkt(), false);
+
+		assert!(!ergebnis.erfolg);
+		assert_eq!(ergebnis.meldung, sprache().set_desk_unknown);
+	}
+
+	#[test]
+	fn portal_ergebnis_ohne_blockieren_abfragen() {
+		let (sender, empfaenger) = channel();
+		let portal = PortalEinrichtung { rueckmeldung: empfaenger, sprachpaket: sprache(), beginn: Instant::now() };
+
+		assert!(portal.ergebnis().is_none());
+		sender.send(Ok("/org/freedesktop/portal/desktop/session/1_2/emoji_picker".to_string())).unwrap();
+		let ergebnis = portal.ergebnis().unwrap();
+		assert!(ergebnis.erfolg);
+		assert_eq!(ergebnis.meldung, sprache().setup_done_portal);
+
+		// Dienst-Thread beendet, ohne zu antworten
+		drop(sender);
+		assert!(!portal.ergebnis().unwrap().erfolg);
+
+		let (sender, empfaenger) = channel();
+		let portal = PortalEinrichtung { rueckmeldung: empfaenger, sprachpaket: sprache(), beginn: Instant::now() };
+		sender.send(Err("emoji_picker_bind: cancelled".to_string())).unwrap();
+		let ergebnis = portal.warte();
+		assert!(!ergebnis.erfolg);
+		assert!(ergebnis.meldung.ends_with("emoji_picker_bind: cancelled"));
+	}
+
+	#[test]
+	fn trockenlauf_gnome_meldet_nur() {
+		let umgebung = Attrappe::neu("GNOME");
+		let trocken = Trockenlauf { echt: &umgebung };
+
+		let ergebnis = setup_shortcut(&trocken, sprache(), &super_punkt(), false);
+
+		assert!(ergebnis.erfolg);
+		assert!(umgebung.befehle.borrow().is_empty());
+		assert_eq!(umgebung.gsettings(GSD_SCHEMA, "custom-keybindings"), None);
+		let eintrag = gnome_eintrag(&format!("{}custom0/", GSD_LISTE_PFAD));
+		assert!(gemeldet(&umgebung, &format!("gsettings set {} name 'Emoji Picker'", eintrag)));
+		assert!(gemeldet(&umgebung, &format!("gsettings set {} binding '<Super>period'", eintrag)));
+		assert!(gemeldet(&umgebung, &format!(
+			"gsettings set {} custom-keybindings '[{}]'",
+			GSD_SCHEMA,
+			format!("'{}custom0/'", GSD_LISTE_PFAD).replace('\'', "'\\''"),
+		)));
+	}
+
+	#[test]
+	fn trockenlauf_kde_meldet_dbus_aufruf() {
+		let umgebung = Attrappe::neu("KDE");
+		let trocken = Trockenlauf { echt: &umgebung };
+
+		let ergebnis = setup_shortcut(&trocken, sprache(), &super_punkt(), false);
+
+		assert!(ergebnis.erfolg);
+		assert!(umgebung.meldungen.borrow()[0].starts_with("# D-Bus org.kde.kglobalaccel setForeignShortcutKeys"));
+		assert_eq!(umgebung.datei(konfig("kglobalshortcutsrc")), None);
+	}
+
+	#[test]
+	fn trockenlauf_sway_zeigt_aenderungen() {
+		let umgebung = Attrappe::neu("sway").mit_datei(konfig("sway/config"), "set $mod Mod4\n");
+		let trocken = Trockenlauf { echt: &umgebung };
+
+		let ergebnis = setup_shortcut(&trocken, sprache(), &super_punkt(), false);
+
+		assert!(ergebnis.erfolg);
+		let pfad = konfig("sway/config").display().to_string();
+		assert!(gemeldet(&umgebung, &format!("cp {} {}.emoji-picker.bak", pfad, pfad)));
+		assert!(gemeldet(&umgebung, &format!("# {}", pfad)));
+		assert!(gemeldet(&umgebung, "+ bindsym Mod4+period exec --no-startup-id emoji-picker"));
+		assert!(gemeldet(&umgebung, "swaymsg reload"));
+		assert!(!gemeldet(&umgebung, "+ set $mod Mod4"));
+		assert_eq!(umgebung.datei(konfig("sway/config")).unwrap(), "set $mod Mod4\n");
+		assert_eq!(umgebung.datei(konfig("sway/config.emoji-picker.bak")), None);
+		assert!(umgebung.befehle.borrow().is_empty());
+	}
+
+	#[test]
+	fn trockenlauf_entfernen_ueber_befehl() {
+		let inhalt = format!("set $mod Mod4\n\n{}\nbindsym Mod4+period exec --no-startup-id emoji-picker\n{}\n", WM_MARKER_START, WM_MARKER_ENDE);
+		let umgebung = Attrappe::neu("sway").mit_datei(konfig("sway/config"), &inhalt);
+
+		let code = shortcut_befehl(&umgebung, ShortcutAktion::Remove, true, sprache(), false);
+
+		assert_eq!(code, 0);
+		assert!(gemeldet(&umgebung, "- bindsym Mod4+period exec --no-startup-id emoji-picker"));
+		assert_eq!(umgebung.datei(konfig("sway/config")).unwrap(), inhalt);
+	}
+
+	#[test]
+	fn trockenlauf_installieren_ueber_befehl() {
+		let umgebung = Attrappe::neu("sway").mit_datei(konfig("sway/config"), "set $mod Mod4\n");
+		let vorher = umgebung.alle_dateien();
+
+		let code = shortcut_befehl(&umgebung, ShortcutAktion::Install, true, sprache(), false);
+
+		assert_eq!(code, 0);
+		assert!(gemeldet(&umgebung, "+ bindsym Mod4+period exec --no-startup-id emoji-picker"));
+		assert_eq!(umgebung.alle_dateien(), vorher);
+		assert!(umgebung.befehle.borrow().is_empty());
+		assert!(!settings::settings_pfad().exists());
+	}
 }
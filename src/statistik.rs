@@ -0,0 +1,368 @@
+use serde::Serialize;
+use std::{collections::HashMap, fs, path::Path, rc::Rc};
+
+use crate::cli::Ausgabeformat;
+use crate::emoji_tabs::{self, Symbol};
+use crate::i18n::Sprache;
+use crate::konsole;
+
+// ╔══════════════════════════════════════════════════════════════╗
+// ║      emoji-picker stats [--top N] [--by-category] [--format] ║
+// ╚══════════════════════════════════════════════════════════════╝
+//
+//   *.list  "😂 42: lachen …"   ──► Zähler je Emoji (seit jeher, wie der 🕓-Tab)
+//   usage.log "1760870400 😂"   ──► zuletzt genutzt + Summen je Monat (ab Einführung des Protokolls)
+//             "1760870400 😂 17"     (zusammengefasst: 17 Nutzungen in dem Monat, zuletzt zur angegebenen Zeit)
+//         │
+//         ▼
+//   Rangliste  ──oder──  Summen je Kategorie (--by-category)
+//         │
+//         └─► table (Standard) | json (alles in einem Objekt) | csv (eine Tabelle)
+
+#[derive(Serialize)]
+struct Rang<'a> {
+    rank: usize,
+    emoji: &'a str,
+    count: usize,
+    category: &'a str,
+    last_used: Option<String>,
+    keywords: &'a [String],
+}
+
+#[derive(Serialize)]
+struct KategorieSumme {
+    category: &'static str,
+    icon: &'static str,
+    emojis: usize,
+    count: usize,
+    share: f64,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+struct Monat {
+    month: String,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct Bericht<'a> {
+    total: usize,
+    emojis: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top: Option<Vec<Rang<'a>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    categories: Option<Vec<KategorieSumme>>,
+    months: Vec<Monat>,
+}
+
+// Aus usage.log: letzte Nutzung je Emoji (ohne Variantenauswahl) und Nutzungen je Monat
+struct Protokoll {
+    zuletzt: HashMap<String, i64>,
+    monate: Vec<Monat>,
+}
+
+// Ab dieser Größe fasst protokolliere_nutzung() usage.log zusammen (ca. 30.000 Auswahlen)
+pub const PROTOKOLL_GRENZE: u64 = 512 * 1024;
+
+// Zeilen "Zeit Emoji [Anzahl]", unlesbare Zeilen werden übersprungen
+fn eintraege(inhalt: &str) -> impl Iterator<Item = (i64, &str, usize)> {
+    inhalt.lines().filter_map(|zeile| {
+        let mut teile = zeile.split_whitespace();
+        let sekunden = teile.next()?.parse::<i64>().ok()?;
+        let emoji = teile.next()?;
+        let anzahl = match teile.next() {
+            Some(anzahl) => anzahl.parse::<usize>().ok()?,
+            None => 1,
+        };
+        Some((sekunden, emoji, anzahl))
+    })
+}
+
+fn lade_protokoll(debug: bool) -> Protokoll {
+    let pfad = emoji_tabs::nutzungsprotokoll();
+    let inhalt = fs::read_to_string(&pfad).unwrap_or_default();
+    let (protokoll, gesamt) = werte_protokoll_aus(&inhalt);
+
+    if debug {
+        eprintln!("📈 {:?}: {} entries", pfad, gesamt);
+    }
+    protokoll
+}
+
+// Protokoll und Zahl der Nutzungen aus dem Inhalt von usage.log
+fn werte_protokoll_aus(inhalt: &str) -> (Protokoll, usize) {
+    let mut zuletzt: HashMap<String, i64> = HashMap::new();
+    let mut monate: HashMap<String, usize> = HashMap::new();
+    let mut gesamt = 0;
+
+    for (sekunden, emoji, anzahl) in eintraege(inhalt) {
+        gesamt += anzahl;
+
+        let letzte = zuletzt.entry(konsole::ohne_variante(emoji)).or_insert(sekunden);
+        *letzte = (*letzte).max(sekunden);
+        if let Some(monat) = datum(sekunden, "%Y-%m") {
+            *monate.entry(monat).or_default() += anzahl;
+        }
+    }
+
+    let mut monate: Vec<Monat> = monate.into_iter().map(|(month, count)| Monat { month, count }).collect();
+    monate.sort_by(|a, b| a.month.cmp(&b.month));
+    (Protokoll { zuletzt, monate }, gesamt)
+}
+
+// Fasst alle Zeilen je Monat und Emoji zu "letzte Zeit, Emoji, Anzahl" zusammen.
+// Monatssummen und letzte Nutzung bleiben exakt erhalten, die Datei wächst danach nur noch
+// mit der Zahl der Monate und verschiedenen Emojis. Schreibt über eine temporäre Datei.
+pub fn verdichte_protokoll(pfad: &Path) -> std::io::Result<()> {
+    let inhalt = fs::read_to_string(pfad)?;
+
+    let mut gruppen: HashMap<(String, &str), (i64, usize)> = HashMap::new();
+    for (sekunden, emoji, anzahl) in eintraege(&inhalt) {
+        let monat = datum(sekunden, "%Y-%m").unwrap_or_default();
+        let gruppe = gruppen.entry((monat, emoji)).or_insert((sekunden, 0));
+        gruppe.0 = gruppe.0.max(sekunden);
+        gruppe.1 += anzahl;
+    }
+
+    let mut zeilen: Vec<(i64, &str, usize)> = gruppen
+        .into_iter()
+        .map(|((_, emoji), (sekunden, anzahl))| (sekunden, emoji, anzahl))
+        .collect();
+    zeilen.sort();
+
+    let neu: String = zeilen
+        .iter()
+        .map(|(sekunden, emoji, anzahl)| format!("{} {} {}\n", sekunden, emoji, anzahl))
+        .collect();
+    let temporaer = pfad.with_extension("log.tmp");
+    fs::write(&temporaer, neu)?;
+    fs::rename(&temporaer, pfad)
+}
+
+// Feld nach RFC 4180: mit Komma, Anführungszeichen oder Zeilenumbruch in "…", " verdoppelt
+fn csv_feld(text: &str) -> String {
+    if text.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+// Unix-Zeit in lokaler Zeitzone formatiert
+fn datum(sekunden: i64, format: &str) -> Option<String> {
+    glib::DateTime::from_unix_local(sekunden)
+        .and_then(|zeit| zeit.format(format))
+        .ok()
+        .map(String::from)
+}
+
+fn kategorie_symbol(datei: &str) -> &'static str {
+    crate::KATEGORIEN
+        .iter()
+        .find(|(name, _)| *name == datei)
+        .map(|(_, symbol)| *symbol)
+        .unwrap_or("")
+}
+
+pub fn statistik_befehl(
+    top: Option<usize>,
+    nach_kategorie: bool,
+    format: Ausgabeformat,
+    sprachpaket: Rc<Sprache>,
+    debug: bool,
+) -> i32 {
+    let kategorien = konsole::lade_kategorien(Rc::clone(&sprachpaket), debug);
+    let protokoll = lade_protokoll(debug);
+
+    // Genutzte Emojis nach Häufigkeit, bei Gleichstand in Kategorie-Reihenfolge
+    let mut genutzt: Vec<(&'static str, &Symbol)> = kategorien
+        .iter()
+        .flat_map(|(datei, symbole)| symbole.iter().map(move |symbol| (*datei, symbol)))
+        .filter(|(_, symbol)| symbol.zaehler > 0)
+        .collect();
+    genutzt.sort_by_key(|(_, symbol)| std::cmp::Reverse(symbol.zaehler));
+
+    let gesamt: usize = genutzt.iter().map(|(_, symbol)| symbol.zaehler).sum();
+
+    let rangliste = || -> Vec<Rang> {
+        genutzt
+            .iter()
+            .take(top.unwrap_or(usize::MAX))
+            .enumerate()
+            .map(|(i, (datei, symbol))| Rang {
+                rank: i + 1,
+                emoji: &symbol.emoji,
+                count: symbol.zaehler,
+                category: datei.strip_suffix(".list").unwrap_or(datei),
+                last_used: protokoll
+                    .zuletzt
+                    .get(&konsole::ohne_variante(&symbol.emoji))
+                    .and_then(|sekunden| datum(*sekunden, "%Y-%m-%d")),
+                keywords: &symbol.begriffe,
+            })
+            .collect()
+    };
+
+    let summen = || -> Vec<KategorieSumme> {
+        kategorien
+            .iter()
+            .map(|(datei, symbole)| {
+                let count: usize = symbole.iter().map(|symbol| symbol.zaehler).sum();
+                KategorieSumme {
+                    category: datei.strip_suffix(".list").unwrap_or(datei),
+                    icon: kategorie_symbol(datei),
+                    emojis: symbole.iter().filter(|symbol| symbol.zaehler > 0).count(),
+                    count,
+                    share: (count * 1000).checked_div(gesamt).unwrap_or(0) as f64 / 10.0,     // Prozent, eine Nachkommastelle
+                }
+            })
+            .collect()
+    };
+
+    match format {
+        Ausgabeformat::Json => {
+            let bericht = Bericht {
+                total: gesamt,
+                emojis: genutzt.len(),
+                top: (!nach_kategorie).then(rangliste),
+                categories: nach_kategorie.then(summen),
+                months: protokoll.monate,
+            };
+            match serde_json::to_string_pretty(&bericht) {
+                Ok(text) => println!("{}", text),
+                Err(e) => {
+                    eprintln!("❌ JSON: {}", e);
+                    return 1;
+                }
+            }
+        }
+        Ausgabeformat::Csv => {
+            if nach_kategorie {
+                println!("category,emojis,count,share");
+                for summe in summen() {
+                    println!("{},{},{},{:.1}", csv_feld(summe.category), summe.emojis, summe.count, summe.share);
+                }
+            } else {
+                println!("rank,emoji,count,category,last_used");
+                for rang in rangliste() {
+                    println!(
+                        "{},{},{},{},{}",
+                        rang.rank,
+                        csv_feld(rang.emoji),
+                        rang.count,
+                        csv_feld(rang.category),
+                        csv_feld(&rang.last_used.unwrap_or_default())
+                    );
+                }
+            }
+        }
+        Ausgabeformat::Tabelle => {
+            if gesamt == 0 {
+                println!("{}", sprachpaket.stats_none);
+                return 0;
+            }
+
+            if nach_kategorie {
+                println!("📂 {}", sprachpaket.stats_categories);
+                let summen = summen();
+                let meiste = summen.iter().map(|summe| summe.count).max().unwrap_or(1);
+                for summe in summen.iter().filter(|summe| summe.count > 0) {
+                    println!(
+                        "  {} {:<10} {:>6}  {:>5.1} %  {}",
+                        summe.icon,
+                        summe.category,
+                        summe.count,
+                        summe.share,
+                        balken(summe.count, meiste)
+                    );
+                }
+            } else {
+                println!("🏆 {}", sprachpaket.stats_top);
+                for rang in rangliste() {
+                    println!(
+                        "  {:>3}. {}  {:>6}  {:<10} {}",
+                        rang.rank,
+                        rang.emoji,
+                        rang.count,
+                        rang.category,
+                        rang.last_used.unwrap_or_default()
+                    );
+                }
+            }
+
+            println!();
+            println!("📅 {}", sprachpaket.stats_months);
+            if protokoll.monate.is_empty() {
+                println!("  {}", sprachpaket.stats_no_timestamps);
+            }
+            let meiste = protokoll.monate.iter().map(|monat| monat.count).max().unwrap_or(1);
+            for monat in &protokoll.monate {
+                println!("  {}  {:>6}  {}", monat.month, monat.count, balken(monat.count, meiste));
+            }
+
+            println!();
+            println!(
+                "Σ {}",
+                sprachpaket
+                    .stats_total
+                    .replace("{count}", &gesamt.to_string())
+                    .replace("{emojis}", &genutzt.len().to_string())
+            );
+        }
+    }
+    0
+}
+
+// Balken relativ zum größten Wert, höchstens 30 Zeichen
+fn balken(wert: usize, maximum: usize) -> String {
+    let laenge = (wert * 30).div_ceil(maximum.max(1));
+    "█".repeat(laenge)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_felder_nach_rfc_4180() {
+        assert_eq!(csv_feld("😂"), "😂");
+        assert_eq!(csv_feld("2025-01-15"), "2025-01-15");
+        assert_eq!(csv_feld("food,drink"), "\"food,drink\"");
+        assert_eq!(csv_feld("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_feld("zwei\nzeilen"), "\"zwei\nzeilen\"");
+    }
+
+    #[test]
+    fn verdichten_behaelt_monatssummen_und_letzte_nutzung() {
+        // Mitte Januar und Mitte Februar 2025, weit weg von Monatsgrenzen in jeder Zeitzone
+        let januar = 1_736_942_400;
+        let februar = 1_739_620_800;
+        let mut inhalt = String::new();
+        for i in 0..50 {
+            inhalt.push_str(&format!("{} 😂\n", januar + i * 60));
+        }
+        inhalt.push_str(&format!("{} ❤️\n{} ❤\n", januar + 10, januar + 20));
+        inhalt.push_str(&format!("{} 😂 3\n", februar));     // schon zusammengefasste Zeile
+        inhalt.push_str(&format!("{} 😂\nkaputt\n{} 👍\n", februar + 5, februar + 7));
+
+        let ordner = std::env::temp_dir().join(format!("emoji-picker-statistik-{}", std::process::id()));
+        fs::create_dir_all(&ordner).unwrap();
+        let pfad = ordner.join("usage.log");
+        fs::write(&pfad, &inhalt).unwrap();
+
+        let (vorher, gesamt_vorher) = werte_protokoll_aus(&inhalt);
+        verdichte_protokoll(&pfad).unwrap();
+        let verdichtet = fs::read_to_string(&pfad).unwrap();
+        let (nachher, gesamt_nachher) = werte_protokoll_aus(&verdichtet);
+        let _ = fs::remove_dir_all(&ordner);
+
+        assert_eq!(gesamt_vorher, 57);
+        assert_eq!(gesamt_nachher, gesamt_vorher);
+        assert_eq!(nachher.monate, vorher.monate);
+        assert_eq!(nachher.zuletzt, vorher.zuletzt);
+        assert_eq!(nachher.zuletzt["😂"], februar + 5);
+        assert!(verdichtet.contains(&format!("{} 😂 50\n", januar + 49 * 60)));
+        assert_eq!(verdichtet.lines().count(), 5);
+        assert!(!pfad.with_extension("log.tmp").exists());
+    }
+}
@@ -61,7 +61,7 @@ pub fn verbinde_suchfeld(
     entry: &gtk::Entry,
     such_grid: Rc<Grid>,
     stack: Stack,
-    such_index: Rc<Vec<Symbol>>,
+    such_index: Rc<RefCell<Vec<Symbol>>>,
     clipboard: Rc<gtk::gdk::Clipboard>,
     window: Rc<gtk::ApplicationWindow>,
     einstellungen: Rc<Einstellungen>,
@@ -101,6 +101,7 @@ pub fn verbinde_suchfeld(
 
                 let schliessen = einstellungen.fenster_schliessen.get();
                 let emoji_size = einstellungen.emoji_size.get();
+                let mit_standard = einstellungen.suche_englisch.get();
 
                 let text = entry.text().to_string();
                 let filter_text = text.trim().to_lowercase();
@@ -119,35 +120,9 @@ pub fn verbinde_suchfeld(
                     such_grid.remove(&widget);
                 }
 
-                let filter_kompakt = filter_text.replace(' ', "");
-                let filter_wörter: Vec<_> = filter_text
-                    .split_whitespace()
-                    .filter(|w| !w.is_empty())
-                    .collect();
-
                 let mut i = 0;
-                for symbol in such_index.iter() {
-                    let joined = symbol.begriffe.join("").to_lowercase();
-                    let begriffe_vec = symbol.begriffe.iter().map(|s| s.to_lowercase()).collect::<Vec<_>>();
-
-                    let kombis_fenster = if begriffe_vec.len() >= 2 {
-                        Some(
-                            (2..=begriffe_vec.len())
-                                .flat_map(|n| begriffe_vec.windows(n).map(|w| w.join("")))
-                                .collect::<Vec<_>>(),
-                        )
-                    } else {
-                        None
-                    };
-
-                    let passt = joined.contains(&filter_kompakt)
-                        || filter_wörter.iter().all(|wort| symbol.begriffe.iter().any(|b| b.contains(wort)))
-                        || kombis_fenster
-                            .as_ref()
-                            .map(|kombis| kombis.iter().any(|k| k.contains(&filter_kompakt)))
-                            .unwrap_or(false);
-
-                    if !passt {
+                for symbol in such_index.borrow().iter() {
+                    if !passt(symbol, &filter_text, mit_standard) {
                         continue; // ❌ Überspringen, wenn nicht passt
                     }
 
@@ -162,7 +137,7 @@ pub fn verbinde_suchfeld(
                     button.add_css_class("emoji");
                     button.set_hexpand(false);
                     button.set_halign(gtk::Align::Center);
-                    button.set_tooltip_text(Some(&symbol.begriffe.join(", ")));
+                    button.set_tooltip_text(Some(&symbol.suchbegriffe(mit_standard).join(", ")));
 
                     let emoji = symbol.emoji.clone();
                     let clipboard = Rc::clone(&clipboard);
@@ -192,3 +167,44 @@ pub fn verbinde_suchfeld(
         }
     });
 }
+
+// Flache Liste aller Symbole für die Suche, ohne den 🕓-Verlauf (der enthält nur Kopien)
+pub fn erstelle_index(emojies_daten: &HashMap<String, (Vec<Symbol>, Rc<Grid>)>) -> Vec<Symbol> {
+    emojies_daten
+        .iter()
+        .filter(|(label, _)| *label != "🕓")
+        .flat_map(|(_, (symbole, _))| symbole.clone())
+        .collect()
+}
+
+// Vergleicht ein Symbol mit dem (kleingeschriebenen) Suchtext – auch für `emoji-picker search`.
+// mit_standard: neben den Begriffen der Sprache auch die aus der .list durchsuchen
+pub fn passt(symbol: &Symbol, filter_text: &str, mit_standard: bool) -> bool {
+    let begriffe = symbol.suchbegriffe(mit_standard);
+
+    let filter_kompakt = filter_text.replace(' ', "");
+    let filter_wörter: Vec<_> = filter_text
+        .split_whitespace()
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let joined = begriffe.join("").to_lowercase();
+    let begriffe_vec = begriffe.iter().map(|s| s.to_lowercase()).collect::<Vec<_>>();
+
+    let kombis_fenster = if begriffe_vec.len() >= 2 {
+        Some(
+            (2..=begriffe_vec.len())
+                .flat_map(|n| begriffe_vec.windows(n).map(|w| w.join("")))
+                .collect::<Vec<_>>(),
+        )
+    } else {
+        None
+    };
+
+    joined.contains(&filter_kompakt)
+        || filter_wörter.iter().all(|wort| begriffe_vec.iter().any(|b| b.contains(wort)))
+        || kombis_fenster
+            .as_ref()
+            .map(|kombis| kombis.iter().any(|k| k.contains(&filter_kompakt)))
+            .unwrap_or(false)
+}
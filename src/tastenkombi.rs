@@ -0,0 +1,226 @@
+use gtk::gdk;
+
+// ╔══════════════════════════════════════════════════════════════╗
+// ║        Tastenkombination in den Formaten der Backends        ║
+// ╚══════════════════════════════════════════════════════════════╝
+//
+//   settings.ini: tastenkombi = <Super>period   (GTK-Accelerator)
+//         │
+//         ▼
+//   Tastenkombi::aus_gtk()
+//         │
+//         ├──► gtk()     "<Super>period"      GNOME, Cinnamon, Budgie, Pantheon
+//         ├──► mate()    "<Mod4>period"       MATE / Marco
+//         ├──► xfce()    "<Super>period"      xfconf (Strg als <Primary>)
+//         ├──► kde()     "Meta+." + Qt-Code   kglobalaccel / kglobalshortcutsrc
+//         ├──► lxqt()    "Meta+period"        lxqt-globalkeysd
+//         ├──► portal()  "LOGO+period"        xdg-desktop-portal
+//         ├──► i3()      "Mod4+period"        i3, sway (bindsym)
+//         ├──► hyprland() "SUPER" + "period" Hyprland (bind)
+//         ├──► river()   "Super" + "period"   river (riverctl map)
+//         ├──► sxhkd()   "super + period"     bspwm (sxhkdrc)
+//         └──► anzeige() "Super+."            Meldungen im Dialog
+
+pub const STANDARD: &str = "<Super>period";
+
+// Qt::KeyboardModifier
+const QT_SHIFT: i32 = 0x0200_0000;
+const QT_CTRL: i32 = 0x0400_0000;
+const QT_ALT: i32 = 0x0800_0000;
+const QT_META: i32 = 0x1000_0000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tastenkombi {
+    pub super_taste: bool,
+    pub strg: bool,
+    pub alt: bool,
+    pub umschalt: bool,
+    pub taste: String,      // Keysym-Name, z.B. "period", "e", "F12"
+}
+
+impl Default for Tastenkombi {
+    fn default() -> Self {
+        Tastenkombi::aus_gtk(STANDARD).expect("gültige Standard-Tastenkombi")
+    }
+}
+
+impl Tastenkombi {
+    // Liest einen GTK-Accelerator wie "<Super><Shift>e" (auch "<Mod4>", "<Primary>", "<Control>")
+    pub fn aus_gtk(text: &str) -> Option<Self> {
+        let mut kombi = Tastenkombi {
+            super_taste: false,
+            strg: false,
+            alt: false,
+            umschalt: false,
+            taste: String::new(),
+        };
+
+        let mut rest = text.trim();
+        while let Some(modifier) = rest.strip_prefix('<') {
+            let (name, danach) = modifier.split_once('>')?;
+            match name.to_lowercase().as_str() {
+                "super" | "mod4" | "meta" | "hyper"    => kombi.super_taste = true,
+                "primary" | "control" | "ctrl" | "ctl" => kombi.strg = true,
+                "alt" | "mod1"                         => kombi.alt = true,
+                "shift" | "shft"                       => kombi.umschalt = true,
+                _                                      => return None,
+            }
+            rest = danach;
+        }
+
+        if rest.is_empty() || rest.contains(['<', '>', ' ']) {
+            return None;
+        }
+        kombi.taste = rest.to_string();
+        Some(kombi)
+    }
+
+    // Ohne Modifier sind nur Funktionstasten als globales Kürzel sinnvoll
+    pub fn ist_gueltig(&self) -> bool {
+        self.super_taste || self.strg || self.alt || self.umschalt || self.funktionstaste().is_some()
+    }
+
+    fn funktionstaste(&self) -> Option<i32> {
+        self.taste
+            .strip_prefix('F')
+            .and_then(|nr| nr.parse::<i32>().ok())
+            .filter(|nr| (1..=35).contains(nr))
+    }
+
+    // Das Zeichen der Taste (z.B. '.' für "period"), falls sie eines erzeugt
+    fn zeichen(&self) -> Option<char> {
+        gdk::Key::from_name(&self.taste)
+            .and_then(|taste| taste.to_unicode())
+            .filter(|zeichen| !zeichen.is_control() && !zeichen.is_whitespace())
+    }
+
+    // Namen der gedrückten Modifier in der Reihenfolge Strg, Alt, Umschalt, Super
+    fn modifier<'a>(&self, namen: [&'a str; 4]) -> Vec<&'a str> {
+        let aktiv = [self.strg, self.alt, self.umschalt, self.super_taste];
+        namen
+            .iter()
+            .zip(aktiv)
+            .filter(|(_, an)| *an)
+            .map(|(name, _)| *name)
+            .collect()
+    }
+
+    fn mit_modifiern(&self, namen: [&str; 4], trenner: &str, taste: &str) -> String {
+        let mut teile = self.modifier(namen);
+        teile.push(taste);
+        teile.join(trenner)
+    }
+
+    // GNOME, Cinnamon, Budgie, Pantheon (gsettings) – auch das Format in settings.ini
+    pub fn gtk(&self) -> String {
+        self.mit_modifiern(["<Control>", "<Alt>", "<Shift>", "<Super>"], "", &self.taste)
+    }
+
+    pub fn mate(&self) -> String {
+        self.mit_modifiern(["<Control>", "<Alt>", "<Shift>", "<Mod4>"], "", &self.taste)
+    }
+
+    pub fn xfce(&self) -> String {
+        self.mit_modifiern(["<Primary>", "<Alt>", "<Shift>", "<Super>"], "", &self.taste)
+    }
+
+    pub fn lxqt(&self) -> String {
+        self.mit_modifiern(["Control", "Alt", "Shift", "Meta"], "+", &self.taste)
+    }
+
+    // Modifier laut xdg-desktop-portal: CTRL, ALT, SHIFT, NUM, LOGO
+    pub fn portal(&self) -> String {
+        self.mit_modifiern(["CTRL", "ALT", "SHIFT", "LOGO"], "+", &self.taste)
+    }
+
+    pub fn i3(&self) -> String {
+        self.mit_modifiern(["Ctrl", "Mod1", "Shift", "Mod4"], "+", &self.taste)
+    }
+
+    // Hyprland trennt Modifier (mit Leerzeichen) und Taste: "bind = SUPER SHIFT, e, ..."
+    pub fn hyprland(&self) -> (String, String) {
+        (self.modifier(["CTRL", "ALT", "SHIFT", "SUPER"]).join(" "), self.taste.clone())
+    }
+
+    // riverctl map erwartet "None", wenn kein Modifier gedrückt ist
+    pub fn river(&self) -> (String, String) {
+        let modifier = self.modifier(["Control", "Alt", "Shift", "Super"]);
+        let modifier = if modifier.is_empty() { "None".to_string() } else { modifier.join("+") };
+        (modifier, self.taste.clone())
+    }
+
+    pub fn sxhkd(&self) -> String {
+        self.mit_modifiern(["ctrl", "alt", "shift", "super"], " + ", &self.taste)
+    }
+
+    pub fn anzeige(&self) -> String {
+        let taste = match self.zeichen() {
+            Some(zeichen) => zeichen.to_uppercase().to_string(),
+            None => self.taste.clone(),
+        };
+        self.mit_modifiern(["Ctrl", "Alt", "Shift", "Super"], "+", &taste)
+    }
+
+    // Taste als Qt-Keycode und Text (QKeySequence::PortableText), None bei unbekannten Tasten
+    fn qt_taste(&self) -> Option<(i32, String)> {
+        let sonder = match self.taste.as_str() {
+            "space"                => Some((0x20, "Space")),
+            "Escape"               => Some((0x0100_0000, "Esc")),
+            "Tab"                  => Some((0x0100_0001, "Tab")),
+            "BackSpace"            => Some((0x0100_0003, "Backspace")),
+            "Return"               => Some((0x0100_0004, "Return")),
+            "Insert"               => Some((0x0100_0006, "Ins")),
+            "Delete"               => Some((0x0100_0007, "Del")),
+            "Pause"                => Some((0x0100_0008, "Pause")),
+            "Print"                => Some((0x0100_0009, "Print")),
+            "Home"                 => Some((0x0100_0010, "Home")),
+            "End"                  => Some((0x0100_0011, "End")),
+            "Left"                 => Some((0x0100_0012, "Left")),
+            "Up"                   => Some((0x0100_0013, "Up")),
+            "Right"                => Some((0x0100_0014, "Right")),
+            "Down"                 => Some((0x0100_0015, "Down")),
+            "Page_Up" | "Prior"    => Some((0x0100_0016, "PgUp")),
+            "Page_Down" | "Next"   => Some((0x0100_0017, "PgDown")),
+            _                      => None,
+        };
+        if let Some((code, text)) = sonder {
+            return Some((code, text.to_string()));
+        }
+
+        if let Some(nr) = self.funktionstaste() {
+            return Some((0x0100_0030 + nr - 1, format!("F{}", nr)));
+        }
+
+        // Druckbare Tasten: Qt nutzt den Unicode-Wert des Großbuchstabens
+        let zeichen = self.zeichen()?.to_uppercase().next()?;
+        Some((zeichen as i32, zeichen.to_string()))
+    }
+
+    // KDE: Text wie "Meta+Shift+E" und der passende Qt-Keycode
+    pub fn kde(&self) -> Option<(i32, String)> {
+        let (taste, text) = self.qt_taste()?;
+
+        let mut code = taste;
+        let mut teile = Vec::new();
+        for (an, modifier, name) in [
+            (self.super_taste, QT_META, "Meta"),
+            (self.strg, QT_CTRL, "Ctrl"),
+            (self.alt, QT_ALT, "Alt"),
+            (self.umschalt, QT_SHIFT, "Shift"),
+        ] {
+            if an {
+                code |= modifier;
+                teile.push(name.to_string());
+            }
+        }
+        teile.push(text);
+        Some((code, teile.join("+")))
+    }
+}
+
+// Liest die gespeicherte Tastenkombi, ungültige Einträge fallen auf Super+. zurück
+pub fn aus_einstellung(text: &str) -> Tastenkombi {
+    Tastenkombi::aus_gtk(text)
+        .filter(|kombi| kombi.ist_gueltig())
+        .unwrap_or_default()
+}
@@ -0,0 +1,548 @@
+use dbus::blocking::Connection;
+use std::{
+    fs,
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+// ╔══════════════════════════════════════════════════════════════╗
+// ║        Zugriff auf Systembefehle und Umgebungsvariablen      ║
+// ╚══════════════════════════════════════════════════════════════╝
+//
+//   shortcut.rs / gtk_theme.rs / portal.rs
+//         │  &dyn Umgebung (vom Aufrufer durchgereicht)
+//         ▼
+//   dyn Umgebung ──► System           (echte Prozesse, std::env, Dateisystem, Session-Bus)
+//                ├─► Trockenlauf      (shortcut --dry-run, ändernde Schritte nur ausgeben)
+//                └─► Attrappe         (Tests: gsettings, xfconf-query, dconf und Dateien im Speicher)
+//
+// Dateien anderer Programme (kglobalshortcutsrc, sway/config, …) und D-Bus-Aufrufe laufen
+// ebenfalls hierüber, damit sich jedes Backend ohne echten Desktop durchspielen lässt.
+
+// Ergebnis eines Befehls, unabhängig von std::process::ExitStatus nachbildbar
+#[derive(Debug, Clone, Default)]
+pub struct Befehlsausgabe {
+    pub code: Option<i32>,      // None = durch Signal beendet
+    pub stdout: String,
+}
+
+impl Befehlsausgabe {
+    pub fn erfolg(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+pub trait Umgebung {
+    // Befehl ausführen und stdout einsammeln (lesende Abfragen wie `gsettings get`)
+    fn abfrage(&self, befehl: &str, args: &[&str]) -> io::Result<Befehlsausgabe>;
+
+    // Befehl ausführen, Ausgabe geht direkt auf das Terminal (ändernde Befehle wie `gsettings set`)
+    fn ausfuehren(&self, befehl: &str, args: &[&str]) -> io::Result<Befehlsausgabe>;
+
+    fn variable(&self, name: &str) -> Option<String>;
+
+    // Namen der Einträge eines Ordners, leer wenn er fehlt
+    fn ordnerinhalt(&self, ordner: &Path) -> Vec<String>;
+
+    fn existiert(&self, pfad: &Path) -> bool;
+
+    fn lies_datei(&self, pfad: &Path) -> io::Result<String>;
+
+    // Legt fehlende Ordner an
+    fn schreibe_datei(&self, pfad: &Path, inhalt: &str) -> io::Result<()>;
+
+    fn kopiere_datei(&self, von: &Path, nach: &Path) -> io::Result<()>;
+
+    fn entferne_datei(&self, pfad: &Path) -> io::Result<()>;
+
+    // ~/.config und ~/.local/share des Benutzers (für Dateien anderer Programme)
+    fn xdg_konfig(&self) -> PathBuf;
+    fn xdg_daten(&self) -> PathBuf;
+    fn heimordner(&self) -> PathBuf;
+
+    // Neue Verbindung zum Session-Bus (kglobalaccel, lxqt-globalkeysd, xdg-desktop-portal)
+    fn sitzungsbus(&self) -> Result<Connection, dbus::Error>;
+
+    // Programm und Argumente, die ein eingerichtetes Tastenkürzel startet
+    fn programmaufruf(&self) -> Vec<String>;
+
+    // Zeile für den Benutzer, z.B. die Schritte eines Trockenlaufs
+    fn melde(&self, zeile: &str) {
+        println!("{}", zeile);
+    }
+
+    // Ändernde Schritte (Dateien, D-Bus) nur ausgeben statt ausführen
+    fn ist_trockenlauf(&self) -> bool {
+        false
+    }
+}
+
+pub struct System;
+
+impl Umgebung for System {
+    fn abfrage(&self, befehl: &str, args: &[&str]) -> io::Result<Befehlsausgabe> {
+        let output = Command::new(befehl).args(args).output()?;
+        Ok(Befehlsausgabe {
+            code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        })
+    }
+
+    fn ausfuehren(&self, befehl: &str, args: &[&str]) -> io::Result<Befehlsausgabe> {
+        let status = Command::new(befehl).args(args).status()?;
+        Ok(Befehlsausgabe {
+            code: status.code(),
+            stdout: String::new(),
+        })
+    }
+
+    fn variable(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok()
+    }
+
+    fn ordnerinhalt(&self, ordner: &Path) -> Vec<String> {
+        fs::read_dir(ordner)
+            .map(|eintraege| {
+                eintraege
+                    .flatten()
+                    .map(|eintrag| eintrag.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn existiert(&self, pfad: &Path) -> bool {
+        pfad.exists()
+    }
+
+    fn lies_datei(&self, pfad: &Path) -> io::Result<String> {
+        fs::read_to_string(pfad)
+    }
+
+    fn schreibe_datei(&self, pfad: &Path, inhalt: &str) -> io::Result<()> {
+        if let Some(ordner) = pfad.parent() {
+            fs::create_dir_all(ordner)?;
+        }
+        fs::write(pfad, inhalt)
+    }
+
+    fn kopiere_datei(&self, von: &Path, nach: &Path) -> io::Result<()> {
+        fs::copy(von, nach).map(|_| ())
+    }
+
+    fn entferne_datei(&self, pfad: &Path) -> io::Result<()> {
+        fs::remove_file(pfad)
+    }
+
+    fn xdg_konfig(&self) -> PathBuf {
+        dirs::config_dir().unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    fn xdg_daten(&self) -> PathBuf {
+        dirs::data_dir().unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    fn heimordner(&self) -> PathBuf {
+        dirs::home_dir().unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    fn sitzungsbus(&self) -> Result<Connection, dbus::Error> {
+        Connection::new_session()
+    }
+
+    fn programmaufruf(&self) -> Vec<String> {
+        crate::pfade::aufruf()
+    }
+}
+
+// Verbindung zu einem Bus an einer bestimmten Adresse (z.B. ein privater Bus in Tests)
+#[cfg(test)]
+pub fn verbinde(adresse: &str) -> Result<Connection, dbus::Error> {
+    let mut kanal = dbus::channel::Channel::open_private(adresse)?;
+    kanal.register()?;
+    Ok(Connection::from(kanal))
+}
+
+#[cfg(test)]
+pub mod attrappe {
+    use super::{Befehlsausgabe, Umgebung, verbinde};
+    use dbus::blocking::Connection;
+    use std::{
+        cell::RefCell,
+        collections::{BTreeMap, HashMap},
+        io,
+        path::{Path, PathBuf},
+    };
+
+    // Nachgebildeter Desktop: gsettings/dconf und xfconf-query arbeiten auf Tabellen im Speicher,
+    // kreadconfig liest die nachgebildeten Dateien. Ändernde Befehle landen zusätzlich in `befehle`.
+    pub struct Attrappe {
+        variablen: HashMap<String, String>,
+        gsettings: RefCell<BTreeMap<(String, String), String>>,     // (Schema, Schlüssel) → GVariant-Text
+        fehlende_schemas: Vec<String>,
+        xfconf: RefCell<BTreeMap<String, String>>,                  // Property → Wert
+        dateien: RefCell<BTreeMap<PathBuf, String>>,
+        fehlende_programme: Vec<String>,
+        bus: Option<String>,
+        aufruf: Vec<String>,
+        pub befehle: RefCell<Vec<String>>,
+        pub meldungen: RefCell<Vec<String>>,
+    }
+
+    pub const HEIM: &str = "/home/test";
+
+    impl Attrappe {
+        pub fn neu(desktop: &str) -> Self {
+            let mut variablen = HashMap::new();
+            if !desktop.is_empty() {
+                variablen.insert("XDG_CURRENT_DESKTOP".to_string(), desktop.to_string());
+            }
+            Attrappe {
+                variablen,
+                gsettings: RefCell::default(),
+                fehlende_schemas: Vec::new(),
+                xfconf: RefCell::default(),
+                dateien: RefCell::default(),
+                fehlende_programme: Vec::new(),
+                bus: None,
+                aufruf: vec!["emoji-picker".to_string()],
+                befehle: RefCell::default(),
+                meldungen: RefCell::default(),
+            }
+        }
+
+        pub fn mit_variable(mut self, name: &str, wert: &str) -> Self {
+            self.variablen.insert(name.to_string(), wert.to_string());
+            self
+        }
+
+        pub fn mit_gsettings(self, schema: &str, schluessel: &str, wert: &str) -> Self {
+            self.gsettings.borrow_mut().insert((schema.to_string(), schluessel.to_string()), als_gvariant(wert));
+            self
+        }
+
+        // Schema (bzw. Schema-Präfix) ist nicht installiert → gsettings endet mit Code 1
+        pub fn ohne_schema(mut self, schema: &str) -> Self {
+            self.fehlende_schemas.push(schema.to_string());
+            self
+        }
+
+        pub fn mit_xfconf(self, property: &str, wert: &str) -> Self {
+            self.xfconf.borrow_mut().insert(property.to_string(), wert.to_string());
+            self
+        }
+
+        pub fn mit_datei(self, pfad: impl AsRef<Path>, inhalt: &str) -> Self {
+            self.dateien.borrow_mut().insert(pfad.as_ref().to_path_buf(), inhalt.to_string());
+            self
+        }
+
+        pub fn ohne_programm(mut self, programm: &str) -> Self {
+            self.fehlende_programme.push(programm.to_string());
+            self
+        }
+
+        pub fn mit_bus(mut self, adresse: &str) -> Self {
+            self.bus = Some(adresse.to_string());
+            self
+        }
+
+        pub fn mit_aufruf(mut self, aufruf: &[&str]) -> Self {
+            self.aufruf = aufruf.iter().map(|teil| teil.to_string()).collect();
+            self
+        }
+
+        pub fn gsettings(&self, schema: &str, schluessel: &str) -> Option<String> {
+            self.gsettings.borrow().get(&(schema.to_string(), schluessel.to_string())).cloned()
+        }
+
+        pub fn xfconf(&self, property: &str) -> Option<String> {
+            self.xfconf.borrow().get(property).cloned()
+        }
+
+        pub fn datei(&self, pfad: impl AsRef<Path>) -> Option<String> {
+            self.dateien.borrow().get(pfad.as_ref()).cloned()
+        }
+
+        pub fn alle_dateien(&self) -> BTreeMap<PathBuf, String> {
+            self.dateien.borrow().clone()
+        }
+
+        fn antwort(&self, befehl: &str, args: &[&str]) -> io::Result<Befehlsausgabe> {
+            if self.fehlende_programme.iter().any(|programm| programm == befehl) {
+                return Err(io::Error::new(io::ErrorKind::NotFound, befehl.to_string()));
+            }
+            match befehl {
+                "gsettings" => Ok(self.gsettings_befehl(args)),
+                "dconf" => Ok(self.dconf_befehl(args)),
+                "xfconf-query" => Ok(self.xfconf_befehl(args)),
+                "kreadconfig5" | "kreadconfig6" => Ok(self.kreadconfig(args)),
+                "swaymsg" | "i3-msg" => Ok(ausgabe(0, "")),
+                _ => Err(io::Error::new(io::ErrorKind::NotFound, befehl.to_string())),
+            }
+        }
+
+        fn gsettings_befehl(&self, args: &[&str]) -> Befehlsausgabe {
+            let schema_fehlt = |schema: &str| self.fehlende_schemas.iter().any(|fehlt| schema.starts_with(fehlt.as_str()));
+            match args {
+                ["get", schema, _] | ["set", schema, _, _] | ["reset", schema, _] if schema_fehlt(schema) => ausgabe(1, ""),
+                ["get", schema, schluessel] => {
+                    let wert = self.gsettings(schema, schluessel).unwrap_or_else(|| {
+                        if schluessel.starts_with("custom-") { "@as []".into() } else { "''".into() }
+                    });
+                    ausgabe(0, &format!("{}\n", wert))
+                }
+                ["set", schema, schluessel, wert] => {
+                    self.gsettings.borrow_mut().insert((schema.to_string(), schluessel.to_string()), als_gvariant(wert));
+                    ausgabe(0, "")
+                }
+                ["reset", schema, schluessel] => {
+                    self.gsettings.borrow_mut().remove(&(schema.to_string(), schluessel.to_string()));
+                    ausgabe(0, "")
+                }
+                _ => ausgabe(1, ""),
+            }
+        }
+
+        // `dconf list /pfad/` → Unterordner aller Einträge von relocatable Schemas unter /pfad/
+        fn dconf_befehl(&self, args: &[&str]) -> Befehlsausgabe {
+            let ["list", ordner] = args else {
+                return ausgabe(1, "");
+            };
+            let mut namen: Vec<String> = self
+                .gsettings
+                .borrow()
+                .keys()
+                .filter_map(|(schema, _)| schema.split_once(':')?.1.strip_prefix(ordner))
+                .filter_map(|rest| rest.split('/').next().map(|name| format!("{}/\n", name)))
+                .collect();
+            namen.dedup();
+            ausgabe(0, &namen.concat())
+        }
+
+        fn xfconf_befehl(&self, args: &[&str]) -> Befehlsausgabe {
+            match args {
+                ["--channel", _, "--list", "--verbose"] => {
+                    let zeilen: String = self
+                        .xfconf
+                        .borrow()
+                        .iter()
+                        .map(|(property, wert)| format!("{:<40} {}\n", property, wert))
+                        .collect();
+                    ausgabe(0, &zeilen)
+                }
+                ["--channel", _, "--property", property, "--create", "--type", "string", "--set", wert] => {
+                    self.xfconf.borrow_mut().insert(property.to_string(), wert.to_string());
+                    ausgabe(0, "")
+                }
+                ["--channel", _, "--property", property, "--reset"] => {
+                    self.xfconf.borrow_mut().remove(*property);
+                    ausgabe(0, "")
+                }
+                _ => ausgabe(1, ""),
+            }
+        }
+
+        // kreadconfig5 --file kglobalshortcutsrc --group A [--group B] --key K
+        fn kreadconfig(&self, args: &[&str]) -> Befehlsausgabe {
+            let mut datei = "";
+            let mut kopf = String::new();
+            let mut schluessel = "";
+            for paar in args.chunks(2) {
+                match paar {
+                    ["--file", wert] => datei = wert,
+                    ["--group", wert] => kopf += &format!("[{}]", wert),
+                    ["--key", wert] => schluessel = wert,
+                    _ => {}
+                }
+            }
+            let inhalt = self.datei(self.xdg_konfig().join(datei)).unwrap_or_default();
+            let wert = inhalt
+                .lines()
+                .skip_while(|zeile| zeile.trim() != kopf)
+                .skip(1)
+                .take_while(|zeile| !zeile.trim_start().starts_with('['))
+                .find_map(|zeile| zeile.trim().strip_prefix(&format!("{}=", schluessel)))
+                .unwrap_or_default();
+            ausgabe(0, &format!("{}\n", wert))
+        }
+    }
+
+    fn ausgabe(code: i32, stdout: &str) -> Befehlsausgabe {
+        Befehlsausgabe { code: Some(code), stdout: stdout.to_string() }
+    }
+
+    // Wie gsettings: Text ohne GVariant-Syntax gilt als String und wird gequotet
+    fn als_gvariant(wert: &str) -> String {
+        if wert.starts_with(['\'', '[', '@', '"']) || wert == "true" || wert == "false" {
+            wert.to_string()
+        } else {
+            format!("'{}'", wert)
+        }
+    }
+
+    fn nicht_gefunden(pfad: &Path) -> io::Error {
+        io::Error::new(io::ErrorKind::NotFound, pfad.display().to_string())
+    }
+
+    impl Umgebung for Attrappe {
+        fn abfrage(&self, befehl: &str, args: &[&str]) -> io::Result<Befehlsausgabe> {
+            self.antwort(befehl, args)
+        }
+
+        fn ausfuehren(&self, befehl: &str, args: &[&str]) -> io::Result<Befehlsausgabe> {
+            self.befehle.borrow_mut().push(format!("{} {}", befehl, args.join(" ")));
+            self.antwort(befehl, args)
+        }
+
+        fn variable(&self, name: &str) -> Option<String> {
+            self.variablen.get(name).cloned()
+        }
+
+        fn ordnerinhalt(&self, ordner: &Path) -> Vec<String> {
+            let mut namen: Vec<String> = self
+                .dateien
+                .borrow()
+                .keys()
+                .filter_map(|pfad| pfad.strip_prefix(ordner).ok()?.iter().next())
+                .map(|name| name.to_string_lossy().to_string())
+                .collect();
+            namen.dedup();
+            namen
+        }
+
+        fn existiert(&self, pfad: &Path) -> bool {
+            self.dateien.borrow().keys().any(|datei| datei.starts_with(pfad))
+        }
+
+        fn lies_datei(&self, pfad: &Path) -> io::Result<String> {
+            self.datei(pfad).ok_or_else(|| nicht_gefunden(pfad))
+        }
+
+        fn schreibe_datei(&self, pfad: &Path, inhalt: &str) -> io::Result<()> {
+            self.dateien.borrow_mut().insert(pfad.to_path_buf(), inhalt.to_string());
+            Ok(())
+        }
+
+        fn kopiere_datei(&self, von: &Path, nach: &Path) -> io::Result<()> {
+            let inhalt = self.lies_datei(von)?;
+            self.schreibe_datei(nach, &inhalt)
+        }
+
+        fn entferne_datei(&self, pfad: &Path) -> io::Result<()> {
+            self.dateien.borrow_mut().remove(pfad).map(|_| ()).ok_or_else(|| nicht_gefunden(pfad))
+        }
+
+        fn xdg_konfig(&self) -> PathBuf {
+            Path::new(HEIM).join(".config")
+        }
+
+        fn xdg_daten(&self) -> PathBuf {
+            Path::new(HEIM).join(".local/share")
+        }
+
+        fn heimordner(&self) -> PathBuf {
+            PathBuf::from(HEIM)
+        }
+
+        fn sitzungsbus(&self) -> Result<Connection, dbus::Error> {
+            match &self.bus {
+                Some(adresse) => verbinde(adresse),
+                None => Err(dbus::Error::new_failed("no session bus")),
+            }
+        }
+
+        fn programmaufruf(&self) -> Vec<String> {
+            self.aufruf.clone()
+        }
+
+        fn melde(&self, zeile: &str) {
+            self.meldungen.borrow_mut().push(zeile.to_string());
+        }
+    }
+}
+
+// Privater dbus-daemon für Tests, wird beim Drop beendet
+#[cfg(test)]
+pub mod testbus {
+    use dbus::{Message, blocking::Connection, channel::Sender, message::MatchRule};
+    use std::{
+        io::{BufRead, BufReader},
+        process::{Child, Command, Stdio},
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+        thread::JoinHandle,
+        time::Duration,
+    };
+
+    pub struct Testbus {
+        pub adresse: String,
+        daemon: Child,
+        laeuft: Arc<AtomicBool>,
+        dienste: Vec<JoinHandle<()>>,
+    }
+
+    impl Testbus {
+        // None, wenn dbus-daemon nicht installiert ist (Test wird dann übersprungen)
+        pub fn starte() -> Option<Self> {
+            let mut daemon = Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .ok()?;
+            let mut adresse = String::new();
+            BufReader::new(daemon.stdout.take()?).read_line(&mut adresse).ok()?;
+            Some(Testbus {
+                adresse: adresse.trim().to_string(),
+                daemon,
+                laeuft: Arc::new(AtomicBool::new(true)),
+                dienste: Vec::new(),
+            })
+        }
+
+        // Nachgebildeter Dienst: `antwort` bekommt jeden Methodenaufruf und darf über die
+        // Verbindung auch Signale senden. None → Standardantwort (leere Rückgabe).
+        pub fn dienst<F>(&mut self, name: &str, mut antwort: F)
+        where
+            F: FnMut(&Message, &Connection) -> Option<Message> + Send + 'static,
+        {
+            let conn = super::verbinde(&self.adresse).expect("test bus");
+            conn.request_name(name, false, true, true).expect("request_name");
+            let laeuft = Arc::clone(&self.laeuft);
+
+            self.dienste.push(std::thread::spawn(move || {
+                use dbus::channel::MatchingReceiver;
+                conn.start_receive(
+                    MatchRule::new_method_call(),
+                    Box::new(move |nachricht, conn| {
+                        let mut nachricht = nachricht;
+                        if nachricht.get_serial().is_none() {
+                            nachricht.set_serial(1);
+                        }
+                        let rueck = antwort(&nachricht, conn).unwrap_or_else(|| nachricht.method_return());
+                        let _ = conn.send(rueck);
+                        true
+                    }),
+                );
+                while laeuft.load(Ordering::Relaxed) {
+                    let _ = conn.process(Duration::from_millis(50));
+                }
+            }));
+        }
+    }
+
+    impl Drop for Testbus {
+        fn drop(&mut self) {
+            self.laeuft.store(false, Ordering::Relaxed);
+            for dienst in self.dienste.drain(..) {
+                let _ = dienst.join();
+            }
+            let _ = self.daemon.kill();
+            let _ = self.daemon.wait();
+        }
+    }
+}
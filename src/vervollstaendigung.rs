@@ -0,0 +1,268 @@
+use crate::cli::{ALLGEMEIN, Eintrag, FENSTER, Shell, UNTERBEFEHLE};
+use crate::i18n::Sprache;
+
+// ╔══════════════════════════════════════════════════════════════╗
+// ║        Shell-Vervollständigung aus den Tabellen in cli.rs    ║
+// ╚══════════════════════════════════════════════════════════════╝
+//
+//   emoji-picker completions bash > ~/.local/share/bash-completion/completions/emoji-picker
+//   emoji-picker completions zsh  > ~/.zfunc/_emoji-picker          (fpath+=~/.zfunc)
+//   emoji-picker completions fish > ~/.config/fish/completions/emoji-picker.fish
+//
+// Beschreibungen kommen aus dem Sprachpaket, Sprach- und Kategorienamen werden beim
+// Erzeugen eingesetzt. Nach neuen Sprachen oder Kategorien das Skript neu erzeugen.
+
+pub fn skript(shell: Shell, sprachpaket: &Sprache) -> String {
+    match shell {
+        Shell::Bash => bash(),
+        Shell::Zsh  => zsh(sprachpaket),
+        Shell::Fish => fish(sprachpaket),
+    }
+}
+
+fn namen(eintraege: &[Eintrag]) -> Vec<&'static str> {
+    eintraege.iter().flat_map(|eintrag| eintrag.namen.iter().copied()).collect()
+}
+
+// Optionen mit diesem Platzhalter (--config-dir DIR) vervollständigen Verzeichnisse
+const ORDNER: &str = "DIR";
+
+// ─── bash ───────────────────────────────────────────────────────
+
+fn bash() -> String {
+    let mut werte_faelle = String::new();
+    for eintrag in ALLGEMEIN.iter().chain(FENSTER).chain(UNTERBEFEHLE.iter().flat_map(|befehl| befehl.optionen)) {
+        if eintrag.wert.is_none() {
+            continue;
+        }
+        let werte = (eintrag.werte)();
+        let antwort = if eintrag.wert == Some(ORDNER) {
+            "compopt -o filenames 2>/dev/null; COMPREPLY=($(compgen -d -- \"$cur\")); return 0".to_string()
+        } else if werte.is_empty() {
+            "return 0".to_string()      // freier Text, keine Vorschläge
+        } else {
+            format!("COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")); return 0", werte.join(" "))
+        };
+        werte_faelle += &format!("        {}) {} ;;\n", eintrag.namen.join("|"), antwort);
+    }
+
+    let allgemein = namen(ALLGEMEIN).join(" ");
+    let mut befehl_faelle = String::new();
+    for befehl in UNTERBEFEHLE {
+        let mut woerter = (befehl.werte)();
+        woerter.extend(namen(befehl.optionen).into_iter().map(String::from));
+        befehl_faelle += &format!(
+            "        {}) COMPREPLY=($(compgen -W \"{} {}\" -- \"$cur\")); return 0 ;;\n",
+            befehl.name,
+            woerter.join(" "),
+            allgemein
+        );
+    }
+
+    let befehle: Vec<&str> = UNTERBEFEHLE.iter().map(|befehl| befehl.name).collect();
+
+    // Optionen vor dem Unterbefehl, deren Wert beim Suchen nach dem Unterbefehl übersprungen wird
+    let mit_wert: Vec<&str> = ALLGEMEIN
+        .iter()
+        .chain(FENSTER)
+        .filter(|eintrag| eintrag.wert.is_some())
+        .flat_map(|eintrag| eintrag.namen.iter().copied())
+        .collect();
+
+    format!(
+        r#"# bash completion for emoji-picker
+_emoji_picker() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    local prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+
+    case "$prev" in
+{werte_faelle}    esac
+
+    # Unterbefehl = erstes Wort, das weder Option noch Wert einer Option ist
+    local befehl="" i
+    for (( i=1; i < COMP_CWORD; i++ )); do
+        case "${{COMP_WORDS[i]}}" in
+            {mit_wert}) (( i++ )) ;;
+            -*) ;;
+            *) befehl="${{COMP_WORDS[i]}}"; break ;;
+        esac
+    done
+
+    case "$befehl" in
+{befehl_faelle}    esac
+
+    if [[ -z $befehl && $cur != -* ]]; then
+        COMPREPLY=($(compgen -W "{befehle}" -- "$cur"))
+    else
+        COMPREPLY=($(compgen -W "{fenster} {allgemein}" -- "$cur"))
+    fi
+}}
+complete -F _emoji_picker emoji-picker
+"#,
+        befehle = befehle.join(" "),
+        mit_wert = mit_wert.join("|"),
+        fenster = namen(FENSTER).join(" "),
+    )
+}
+
+// ─── zsh ────────────────────────────────────────────────────────
+
+// Beschreibung innerhalb von '…[text]…' in einer _arguments-Spezifikation
+fn zsh_text(text: &str) -> String {
+    text.replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+fn zsh_spezifikationen(eintraege: &[Eintrag], sprachpaket: &Sprache) -> Vec<String> {
+    eintraege
+        .iter()
+        .map(|eintrag| {
+            let hilfe = zsh_text((eintrag.hilfe)(sprachpaket));
+            let wert = match eintrag.wert {
+                Some(platzhalter) => {
+                    let werte = (eintrag.werte)();
+                    if platzhalter == ORDNER {
+                        format!(":{}:_files -/", platzhalter)
+                    } else if werte.is_empty() {
+                        format!(":{}: ", platzhalter)
+                    } else {
+                        format!(":{}:({})", platzhalter, werte.join(" "))
+                    }
+                }
+                None => String::new(),
+            };
+            match eintrag.namen {
+                [name] => format!("'{}[{}]{}'", name, hilfe, wert),
+                // Mehrere Namen schließen sich aus, Klammer-Erweiterung außerhalb der Anführungszeichen
+                namen => format!("'({})'{{{}}}'[{}]{}'", namen.join(" "), namen.join(","), hilfe, wert),
+            }
+        })
+        .collect()
+}
+
+// Fortsetzungszeilen der _arguments-Aufrufe
+const EINZUG: &str = " \\\n        ";
+const EINZUG_INNEN: &str = " \\\n                        ";
+
+fn zsh(sprachpaket: &Sprache) -> String {
+    let allgemein = zsh_spezifikationen(ALLGEMEIN, sprachpaket);
+
+    let befehle: Vec<String> = UNTERBEFEHLE
+        .iter()
+        // bei _describe trennt nur der erste Doppelpunkt, die Beschreibung bleibt unverändert
+        .map(|befehl| format!("        '{}:{}'", befehl.name, (befehl.hilfe)(sprachpaket).replace('\'', "'\\''")))
+        .collect();
+
+    let mut faelle = String::new();
+    for befehl in UNTERBEFEHLE {
+        let mut spezifikationen = allgemein.clone();
+        spezifikationen.extend(zsh_spezifikationen(befehl.optionen, sprachpaket));
+
+        let werte = (befehl.werte)();
+        if !werte.is_empty() {
+            spezifikationen.push(format!("'1:{}:({})'", befehl.name, werte.join(" ")));
+        } else if !befehl.argument.is_empty() {
+            spezifikationen.push(format!("'*:{}: '", befehl.argument.trim_matches(['<', '>'])));
+        }
+
+        faelle += &format!(
+            "                {})\n                    _arguments{}{}\n                    ;;\n",
+            befehl.name,
+            EINZUG_INNEN,
+            spezifikationen.join(EINZUG_INNEN)
+        );
+    }
+
+    let mut haupt = allgemein;
+    haupt.extend(zsh_spezifikationen(FENSTER, sprachpaket));
+    haupt.push("'1: :->befehl'".into());
+    haupt.push("'*:: :->argumente'".into());
+
+    format!(
+        r#"#compdef emoji-picker
+
+_emoji_picker() {{
+    local state line
+    local -a befehle
+    befehle=(
+{befehle}
+    )
+
+    _arguments -C{EINZUG}{haupt}
+
+    case $state in
+        befehl)
+            _describe 'command' befehle
+            ;;
+        argumente)
+            case $line[1] in
+{faelle}            esac
+            ;;
+    esac
+}}
+
+_emoji_picker "$@"
+"#,
+        befehle = befehle.join("\n"),
+        haupt = haupt.join(EINZUG),
+    )
+}
+
+// ─── fish ───────────────────────────────────────────────────────
+
+fn fish_text(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn fish_zeilen(eintraege: &[Eintrag], bedingung: &str, sprachpaket: &Sprache) -> String {
+    let mut text = String::new();
+    for eintrag in eintraege {
+        let mut zeile = format!("complete -c emoji-picker -n {}", fish_text(bedingung));
+        for name in eintrag.namen {
+            match name.strip_prefix("--") {
+                Some(lang) => zeile += &format!(" -l {}", lang),
+                None => zeile += &format!(" -s {}", name.trim_start_matches('-')),
+            }
+        }
+        if eintrag.wert.is_some() {
+            zeile += " -x";
+            let werte = (eintrag.werte)();
+            if eintrag.wert == Some(ORDNER) {
+                zeile += " -a '(__fish_complete_directories)'";
+            } else if !werte.is_empty() {
+                zeile += &format!(" -a {}", fish_text(&werte.join(" ")));
+            }
+        }
+        zeile += &format!(" -d {}\n", fish_text((eintrag.hilfe)(sprachpaket)));
+        text += &zeile;
+    }
+    text
+}
+
+fn fish(sprachpaket: &Sprache) -> String {
+    let befehle: Vec<&str> = UNTERBEFEHLE.iter().map(|befehl| befehl.name).collect();
+    let ohne_befehl = format!("not __fish_seen_subcommand_from {}", befehle.join(" "));
+
+    let mut text = String::from("# fish completion for emoji-picker\ncomplete -c emoji-picker -f\n\n");
+    text += &fish_zeilen(ALLGEMEIN, "true", sprachpaket);
+    text += &fish_zeilen(FENSTER, &ohne_befehl, sprachpaket);
+
+    for befehl in UNTERBEFEHLE {
+        text += &format!(
+            "\ncomplete -c emoji-picker -n {} -a {} -d {}\n",
+            fish_text(&ohne_befehl),
+            befehl.name,
+            fish_text((befehl.hilfe)(sprachpaket))
+        );
+
+        let bedingung = format!("__fish_seen_subcommand_from {}", befehl.name);
+        let werte = (befehl.werte)();
+        if !werte.is_empty() {
+            text += &format!("complete -c emoji-picker -n {} -a {}\n", fish_text(&bedingung), fish_text(&werte.join(" ")));
+        }
+        text += &fish_zeilen(befehl.optionen, &bedingung, sprachpaket);
+    }
+    text
+}